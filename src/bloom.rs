@@ -0,0 +1,685 @@
+use std::{
+    collections::hash_map::RandomState,
+    f64::consts::LN_2,
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+};
+
+use crate::{
+    fingerprint::Fingerprint,
+    hasher,
+    store::{
+        self, DedupHashedStore, DedupHashedStoreAdapter, DedupStoreByKey, DedupStoreByKeyAdapter,
+        HashedStore, SeenStore,
+    },
+};
+
+/* # bloom filter */
+
+/// the `hashes` positions below `len` probed for `digest`, derived from its two halves by double hashing
+fn probes(digest: u128, hashes: u32, len: u64) -> impl Iterator<Item = u64> {
+    let first = (digest >> 64) as u64;
+    let step = digest as u64 | 1;
+    (0..u64::from(hashes)).map(move |probe| first.wrapping_add(probe.wrapping_mul(step)) % len)
+}
+
+/// how many times more elements every added filter of a scalable store is sized for
+const GROWTH: usize = 2;
+
+/// how many times lower the false positive rate of every added filter of a scalable store is,
+/// which keeps the rate of the whole store below twice the rate of the first filter
+const TIGHTENING: f64 = 0.5;
+
+/// a bit array sized for `capacity` elements, probed `hashes` times per element
+#[derive(Debug, Clone)]
+struct Filter {
+    words: Vec<u64>,
+    hashes: u32,
+    capacity: usize,
+    rate: f64,
+    len: usize,
+}
+
+impl Filter {
+    fn new(capacity: usize, rate: f64) -> Self {
+        assert!(
+            0.0 < rate && rate < 1.0,
+            "false positive rate must lie between 0 and 1"
+        );
+        let capacity = capacity.max(1);
+        let bits = (-(capacity as f64) * rate.ln() / (LN_2 * LN_2)).ceil();
+        let words = ((bits / 64.0).ceil() as usize).max(1);
+        let hashes = ((words * 64) as f64 / capacity as f64 * LN_2).round();
+        Self {
+            words: vec![0; words],
+            hashes: (hashes as u32).max(1),
+            capacity,
+            rate,
+            len: 0,
+        }
+    }
+
+    fn bit_len(&self) -> u64 {
+        self.words.len() as u64 * 64
+    }
+
+    /// the words and masks of the bits probed for `digest`
+    fn probes(&self, digest: u128) -> impl Iterator<Item = (usize, u64)> {
+        probes(digest, self.hashes, self.bit_len())
+            .map(|bit| ((bit / 64) as usize, 1 << (bit % 64)))
+    }
+
+    fn contains(&self, digest: u128) -> bool {
+        self.probes(digest)
+            .all(|(word, mask)| self.words[word] & mask != 0)
+    }
+
+    fn insert(&mut self, digest: u128) {
+        for (word, mask) in self.probes(digest) {
+            self.words[word] |= mask;
+        }
+        self.len += 1;
+    }
+
+    /// the probability, that an element never inserted is found, given the share of set bits
+    fn false_positive_rate(&self) -> f64 {
+        let set = self.words.iter().map(|word| word.count_ones()).sum::<u32>();
+        (f64::from(set) / self.bit_len() as f64).powi(self.hashes as i32)
+    }
+}
+
+/* # bloom store */
+
+/// remembers elements approximately, in a Bloom filter of about `-n ln p / ln² 2` bits for `n` elements
+///
+/// duplicates are always recognised, but an element never seen before is taken for a duplicate, and dropped,
+/// with probability about `p`; a scalable store adds larger filters as elements come, so that `p` holds for any `n`
+#[derive(Debug, Clone)]
+pub struct BloomStore<T, S = RandomState> {
+    filters: Vec<Filter>,
+    scalable: bool,
+    hash_builder: S,
+    len: usize,
+    item: PhantomData<fn(&T)>,
+}
+
+impl<T> BloomStore<T> {
+    /// sizes the filter for `expected` elements, at false positive `rate`
+    pub fn new(expected: usize, rate: f64) -> Self {
+        Self::with_hasher(expected, rate, RandomState::new())
+    }
+
+    /// sizes the first filter for `initial` elements, adding larger ones to keep the false positive `rate`
+    pub fn scalable(initial: usize, rate: f64) -> Self {
+        Self::scalable_with_hasher(initial, rate, RandomState::new())
+    }
+}
+
+impl<T, S> BloomStore<T, S> {
+    /// as `new`, but hashes the elements with provided `BuildHasher`
+    pub fn with_hasher(expected: usize, rate: f64, hash_builder: S) -> Self {
+        Self::with_filter(Filter::new(expected, rate), false, hash_builder)
+    }
+
+    /// as `scalable`, but hashes the elements with provided `BuildHasher`
+    pub fn scalable_with_hasher(initial: usize, rate: f64, hash_builder: S) -> Self {
+        Self::with_filter(
+            Filter::new(initial, rate * (1.0 - TIGHTENING)),
+            true,
+            hash_builder,
+        )
+    }
+
+    fn insert_digest(&mut self, digest: u128) -> bool {
+        if self.filters.iter().any(|filter| filter.contains(digest)) {
+            return false;
+        }
+        let last = self.filters.last().expect("at least one filter");
+        if self.scalable && last.len >= last.capacity {
+            let next = Filter::new(last.capacity * GROWTH, last.rate * TIGHTENING);
+            self.filters.push(next);
+        }
+        self.filters
+            .last_mut()
+            .expect("at least one filter")
+            .insert(digest);
+        self.len += 1;
+        true
+    }
+
+    fn with_filter(filter: Filter, scalable: bool, hash_builder: S) -> Self {
+        Self {
+            filters: vec![filter],
+            scalable,
+            hash_builder,
+            len: 0,
+            item: PhantomData,
+        }
+    }
+
+    /// the memory taken by the filters, in bits
+    pub fn bit_len(&self) -> u64 {
+        self.filters.iter().map(Filter::bit_len).sum()
+    }
+
+    /// the number of filters, which grows only in a scalable store
+    pub fn filters(&self) -> usize {
+        self.filters.len()
+    }
+
+    /// estimates the probability, that an element never seen before is taken for a duplicate now
+    pub fn false_positive_rate(&self) -> f64 {
+        1.0 - self
+            .filters
+            .iter()
+            .map(|filter| 1.0 - filter.false_positive_rate())
+            .product::<f64>()
+    }
+}
+
+impl<T, S> SeenStore<T> for BloomStore<T, S>
+where
+    T: Hash,
+    S: BuildHasher,
+{
+    fn contains(&self, item: &T) -> bool {
+        let digest = u128::digest(item, &self.hash_builder);
+        self.filters.iter().any(|filter| filter.contains(digest))
+    }
+
+    fn insert(&mut self, item: T) -> bool {
+        self.insert_digest(u128::digest(&item, &self.hash_builder))
+    }
+
+    fn record(&mut self, item: T) -> bool {
+        self.insert_digest(u128::digest(&item, &self.hash_builder))
+    }
+
+    /// records `item` from its digest alone, which is computed once
+    fn record_ref(&mut self, item: &T) -> bool {
+        self.record_hashed(item)
+    }
+
+    /// the number of elements recorded as new, which may be lower than the number of distinct elements
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        self.filters.truncate(1);
+        self.filters[0].words.iter_mut().for_each(|word| *word = 0);
+        self.filters[0].len = 0;
+        self.len = 0;
+    }
+}
+
+impl<T, S> HashedStore<T> for BloomStore<T, S>
+where
+    T: Hash,
+    S: BuildHasher,
+{
+    fn record_hashed(&mut self, item: &T) -> bool {
+        self.insert_digest(u128::digest(item, &self.hash_builder))
+    }
+}
+
+/* # deduplicator */
+
+/// removes equal elements pushed one at a time, remembering them in a Bloom filter
+pub type Deduplicator<T, S = RandomState> = store::Deduplicator<T, BloomStore<T, S>>;
+
+/* # dedup bloom */
+
+/// removes equal elements, remembering them in a Bloom filter, which drops some new ones as well
+pub type DedupBloom<I, S = RandomState> = DedupHashedStore<I, BloomStore<<I as Iterator>::Item, S>>;
+
+/// provides the `dedup_bloom` method on `Iterator`s
+///
+/// a scalable or custom-hashed store can be passed to `dedup_hashed_store` instead
+pub trait DedupBloomAdapter: Iterator {
+    fn dedup_bloom(self, expected: usize, rate: f64) -> DedupBloom<Self>
+    where
+        Self: Sized,
+    {
+        self.dedup_hashed_store(BloomStore::new(expected, rate))
+    }
+}
+
+impl<I> DedupBloomAdapter for I where I: Iterator {}
+
+/* # dedup bloom by key */
+
+/// removes elements, which give equal outputs from provided function, remembering the outputs in a Bloom filter
+pub type DedupBloomByKey<I, F, K, S = RandomState> = DedupStoreByKey<I, BloomStore<K, S>, F, K>;
+
+/// provides the `dedup_bloom_by_key` method on `Iterator`s
+///
+/// a scalable or custom-hashed store can be passed to `dedup_store_by_key` instead
+pub trait DedupBloomByKeyAdapter<F, K>: Iterator {
+    fn dedup_bloom_by_key(
+        self,
+        expected: usize,
+        rate: f64,
+        function: F,
+    ) -> DedupBloomByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(BloomStore::new(expected, rate), function)
+    }
+}
+
+impl<I, F, K> DedupBloomByKeyAdapter<F, K> for I where I: Iterator {}
+
+/* # stable bloom store */
+
+/// the value cells are set to on insertion, so that every cell fits in two bits
+const MAX: u8 = 3;
+
+/// the number of two-bit cells packed into a byte
+const CELLS_PER_BYTE: usize = 4;
+
+/// remembers recent elements approximately, in a stable Bloom filter of small counters
+///
+/// every insertion decrements a run of `decay` cells starting at a random one, before setting the cells of the element,
+/// so old elements are forgotten gradually and the share of set cells settles instead of growing to saturation;
+/// a new element is then dropped with probability at most `false_positive`, while a duplicate is let through
+/// with probability at most `false_negative`, as long as it comes within `window` insertions of the first one
+#[derive(Debug, Clone)]
+pub struct StableBloomStore<T, S = RandomState> {
+    bytes: Vec<u8>,
+    cells: usize,
+    hashes: u32,
+    decay: usize,
+    random: u64,
+    hash_builder: S,
+    len: usize,
+    item: PhantomData<fn(&T)>,
+}
+
+impl<T> StableBloomStore<T> {
+    pub fn new(window: usize, false_positive: f64, false_negative: f64) -> Self {
+        Self::with_hasher(window, false_positive, false_negative, RandomState::new())
+    }
+}
+
+impl<T, S> StableBloomStore<T, S> {
+    /// as `new`, but hashes the elements with provided `BuildHasher`
+    pub fn with_hasher(
+        window: usize,
+        false_positive: f64,
+        false_negative: f64,
+        hash_builder: S,
+    ) -> Self {
+        assert!(
+            0.0 < false_positive && false_positive < 1.0,
+            "false positive rate must lie between 0 and 1"
+        );
+        assert!(
+            0.0 < false_negative && false_negative < 1.0,
+            "false negative rate must lie between 0 and 1"
+        );
+        let hashes = (-false_positive.log2()).ceil() as u32;
+        let fits = |cells| forget_rate(cells, hashes, window, false_positive) <= false_negative;
+        let mut cells = 64.max(2 * hashes as usize);
+        while !fits(cells) {
+            cells *= 2;
+        }
+        let mut fitting = cells / 2;
+        while fitting + 1 < cells {
+            let middle = fitting + (cells - fitting) / 2;
+            if fits(middle) {
+                cells = middle;
+            } else {
+                fitting = middle;
+            }
+        }
+        Self {
+            bytes: vec![0; cells.div_ceil(CELLS_PER_BYTE)],
+            cells,
+            hashes,
+            decay: decay(cells, hashes, false_positive),
+            random: 0,
+            hash_builder,
+            len: 0,
+            item: PhantomData,
+        }
+    }
+
+    /// the number of cells, taking two bits each
+    pub fn cells(&self) -> usize {
+        self.cells
+    }
+
+    /// the memory taken by the cells, in bits
+    pub fn bit_len(&self) -> u64 {
+        self.bytes.len() as u64 * 8
+    }
+
+    /// the number of cells decremented on every insertion
+    pub fn decay(&self) -> usize {
+        self.decay
+    }
+
+    /// the probability, that a new element is taken for a duplicate, once the share of set cells has settled
+    pub fn false_positive_rate(&self) -> f64 {
+        stable_fill(self.cells, self.hashes, self.decay).powi(self.hashes as i32)
+    }
+
+    /// bounds the probability, that an element recorded `age` insertions ago is forgotten
+    pub fn false_negative_rate(&self, age: usize) -> f64 {
+        let x = self.decay as f64 / self.cells as f64;
+        1.0 - survival(age, x).powi(self.hashes as i32)
+    }
+
+    fn next_random(&mut self) -> u64 {
+        let random = hasher::splitmix(self.random);
+        self.random = self.random.wrapping_add(hasher::GAMMA);
+        random
+    }
+
+    fn cell(&self, index: usize) -> u8 {
+        let shift = index % CELLS_PER_BYTE * 2;
+        self.bytes[index / CELLS_PER_BYTE] >> shift & MAX
+    }
+
+    fn set_cell(&mut self, index: usize, value: u8) {
+        let shift = index % CELLS_PER_BYTE * 2;
+        let byte = &mut self.bytes[index / CELLS_PER_BYTE];
+        *byte = *byte & !(MAX << shift) | value << shift;
+    }
+
+    fn holds(&self, digest: u128) -> bool {
+        probes(digest, self.hashes, self.cells as u64).all(|cell| self.cell(cell as usize) > 0)
+    }
+
+    fn record_digest(&mut self, digest: u128) -> bool {
+        if self.holds(digest) {
+            return false;
+        }
+        self.set(digest);
+        self.len += 1;
+        true
+    }
+
+    /// decrements `decay` cells starting at a random one, then sets the cells probed for `digest`
+    fn set(&mut self, digest: u128) {
+        let len = self.cells;
+        let start = (self.next_random() % len as u64) as usize;
+        for offset in 0..self.decay {
+            let cell = (start + offset) % len;
+            self.set_cell(cell, self.cell(cell).saturating_sub(1));
+        }
+        for cell in probes(digest, self.hashes, len as u64) {
+            self.set_cell(cell as usize, MAX);
+        }
+    }
+}
+
+/// the share of non-zero cells, which a stable filter settles at
+fn stable_fill(cells: usize, hashes: u32, decay: usize) -> f64 {
+    let decrements = decay as f64 * (1.0 / f64::from(hashes) - 1.0 / cells as f64);
+    1.0 - (1.0 / (1.0 + 1.0 / decrements)).powi(i32::from(MAX))
+}
+
+/// the smallest number of cells to decrement per insertion, which keeps the false positive rate
+fn decay(cells: usize, hashes: u32, false_positive: f64) -> usize {
+    let fill = false_positive.powf(1.0 / f64::from(hashes));
+    let kept = (1.0 - fill).powf(1.0 / f64::from(MAX));
+    let decay = 1.0 / ((1.0 / f64::from(hashes) - 1.0 / cells as f64) * (1.0 / kept - 1.0));
+    (decay.ceil() as usize).clamp(1, cells)
+}
+
+/// the probability, that a cell hit with probability `x` per insertion is decremented fewer than `MAX` times in `age`
+fn survival(age: usize, x: f64) -> f64 {
+    let age = age as f64;
+    let mut ways = 1.0;
+    let mut sum = 0.0;
+    for hits in 0..MAX {
+        let hits = f64::from(hits);
+        if hits > age {
+            break;
+        }
+        if hits > 0.0 {
+            ways *= (age - hits + 1.0) / hits;
+        }
+        sum += ways * x.powf(hits) * ((-x).ln_1p() * (age - hits)).exp();
+    }
+    sum.min(1.0)
+}
+
+/// bounds the probability, that an element is forgotten within `window` insertions by a filter of `cells` cells
+fn forget_rate(cells: usize, hashes: u32, window: usize, false_positive: f64) -> f64 {
+    let x = decay(cells, hashes, false_positive) as f64 / cells as f64;
+    1.0 - survival(window, x).powi(hashes as i32)
+}
+
+impl<T, S> SeenStore<T> for StableBloomStore<T, S>
+where
+    T: Hash,
+    S: BuildHasher,
+{
+    fn contains(&self, item: &T) -> bool {
+        self.holds(u128::digest(item, &self.hash_builder))
+    }
+
+    /// decays the filter and sets the cells of `item`, even if it was recorded before
+    fn insert(&mut self, item: T) -> bool {
+        let digest = u128::digest(&item, &self.hash_builder);
+        let new = !self.holds(digest);
+        self.set(digest);
+        self.len += usize::from(new);
+        new
+    }
+
+    fn record(&mut self, item: T) -> bool {
+        self.record_digest(u128::digest(&item, &self.hash_builder))
+    }
+
+    fn record_ref(&mut self, item: &T) -> bool {
+        self.record_hashed(item)
+    }
+
+    /// the number of elements recorded as new, including the forgotten ones
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        self.bytes.iter_mut().for_each(|byte| *byte = 0);
+        self.len = 0;
+    }
+}
+
+impl<T, S> HashedStore<T> for StableBloomStore<T, S>
+where
+    T: Hash,
+    S: BuildHasher,
+{
+    /// decays the filter and sets the cells of `item`, unless it is found, computing its digest once
+    fn record_hashed(&mut self, item: &T) -> bool {
+        self.record_digest(u128::digest(item, &self.hash_builder))
+    }
+}
+
+/* # dedup stable bloom */
+
+/// removes elements equal to a recent one, remembering them in a stable Bloom filter
+pub type DedupStableBloom<I, S = RandomState> =
+    DedupHashedStore<I, StableBloomStore<<I as Iterator>::Item, S>>;
+
+/// provides the `dedup_stable_bloom` method on `Iterator`s
+pub trait DedupStableBloomAdapter: Iterator {
+    fn dedup_stable_bloom(
+        self,
+        window: usize,
+        false_positive: f64,
+        false_negative: f64,
+    ) -> DedupStableBloom<Self>
+    where
+        Self: Sized,
+    {
+        self.dedup_hashed_store(StableBloomStore::new(
+            window,
+            false_positive,
+            false_negative,
+        ))
+    }
+}
+
+impl<I> DedupStableBloomAdapter for I where I: Iterator {}
+
+/* # dedup stable bloom by key */
+
+/// removes elements, which give an output from provided function equal to a recent one,
+/// remembering the outputs in a stable Bloom filter
+pub type DedupStableBloomByKey<I, F, K, S = RandomState> =
+    DedupStoreByKey<I, StableBloomStore<K, S>, F, K>;
+
+/// provides the `dedup_stable_bloom_by_key` method on `Iterator`s
+pub trait DedupStableBloomByKeyAdapter<F, K>: Iterator {
+    fn dedup_stable_bloom_by_key(
+        self,
+        window: usize,
+        false_positive: f64,
+        false_negative: f64,
+        function: F,
+    ) -> DedupStableBloomByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(
+            StableBloomStore::new(window, false_positive, false_negative),
+            function,
+        )
+    }
+}
+
+impl<I, F, K> DedupStableBloomByKeyAdapter<F, K> for I where I: Iterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hasher::FastState;
+
+    #[test]
+    fn deduplicate_empty_iterator() {
+        let og = Vec::<u8>::new();
+        let dp = og.iter().dedup_bloom(16, 0.01).count();
+        assert_eq!(dp, 0);
+    }
+
+    #[test]
+    fn remove_duplicate_character() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og
+            .chars()
+            .dedup_hashed_store(BloomStore::with_hasher(64, 0.001, FastState::new()))
+            .collect::<String>();
+        assert_eq!(&dp, "spor dwjnychlite");
+    }
+
+    #[test]
+    fn elements_need_not_be_clone() {
+        #[derive(Debug, Hash)]
+        struct Page(&'static str);
+
+        let og = || ["strona", "podstrona", "strona", "witryna"].map(Page);
+        let dp = og().into_iter().dedup_bloom(16, 0.001);
+        assert_eq!(
+            dp.map(|page| page.0).collect::<Vec<_>>(),
+            ["strona", "podstrona", "witryna"]
+        );
+        let dp = og().into_iter().dedup_stable_bloom(16, 0.001, 0.01).count();
+        assert_eq!(dp, 3);
+    }
+
+    #[test]
+    fn duplicates_are_always_recognised() {
+        let mut dp = (0..10_000)
+            .chain(0..10_000)
+            .dedup_bloom_by_key(10_000, 0.01, |&n| n * 7);
+        let new = dp.by_ref().take_while(|&n| n < 10_000).count();
+        assert!(new > 9_800);
+        assert_eq!(dp.count(), 0);
+    }
+
+    #[test]
+    fn false_positive_rate_is_kept() {
+        let mut store = BloomStore::with_hasher(10_000, 0.01, FastState::new());
+        (0..10_000_u32).for_each(|n| {
+            store.insert(n);
+        });
+        let rate = store.false_positive_rate();
+        assert!((0.005..0.015).contains(&rate));
+        let found = (10_000..20_000_u32).filter(|n| store.contains(n)).count();
+        assert!(found < 200);
+    }
+
+    #[test]
+    fn scalable_store_grows_with_stream() {
+        let mut store = BloomStore::scalable_with_hasher(1_000, 0.01, FastState::new());
+        let bits = store.bit_len();
+        let new = (0..16_000_u32).filter(|&n| store.insert(n)).count();
+        assert!(new > 15_800);
+        assert_eq!(store.filters(), 5);
+        assert!(store.bit_len() > 16 * bits);
+        assert!(store.false_positive_rate() < 0.01);
+        let found = (16_000..32_000_u32).filter(|n| store.contains(n)).count();
+        assert!(found < 160);
+        store.clear();
+        assert_eq!((store.filters(), store.len()), (1, 0));
+    }
+
+    #[test]
+    fn stable_store_parameters_keep_bounds() {
+        let store = StableBloomStore::<u32>::new(1_000, 0.01, 0.01);
+        assert!(store.false_positive_rate() <= 0.01);
+        assert!(store.false_negative_rate(1_000) <= 0.01);
+        assert!(store.false_negative_rate(100_000) > 0.5);
+        let cells = store.cells() as u64;
+        assert!(2 * cells <= store.bit_len() && store.bit_len() < 2 * cells + 8);
+    }
+
+    #[test]
+    fn stable_store_never_saturates() {
+        let mut store = StableBloomStore::with_hasher(1_000, 0.01, 0.01, FastState::new());
+        (0..100_000_u32).for_each(|n| {
+            store.insert(n);
+        });
+        let new = (100_000..110_000_u32).filter(|&n| store.insert(n)).count();
+        assert!(new > 9_800);
+    }
+
+    #[test]
+    fn stable_store_remembers_recent_elements() {
+        let og = (0..20_000_u32).flat_map(|n| [n, n.saturating_sub(500)]);
+        let dp = og
+            .dedup_stable_bloom_by_key(1_000, 0.01, 0.01, |&n| n)
+            .count();
+        assert!((19_800..20_200).contains(&dp));
+    }
+
+    #[test]
+    fn stable_deduplicator_matches_adapter() {
+        let store = || StableBloomStore::with_hasher(1_000, 0.01, 0.01, FastState::new());
+        let og = (0..20_000_u32).flat_map(|n| [n, n.saturating_sub(500)]);
+        let mut dd = store::Deduplicator::with_store(store());
+        let pushed = og.clone().filter(|&n| dd.insert(n)).collect::<Vec<_>>();
+        let dp = og.dedup_hashed_store(store()).collect::<Vec<_>>();
+        assert_eq!(pushed, dp);
+        assert_eq!(dd.len(), dp.len());
+    }
+
+    #[test]
+    fn stable_store_deduplicates_characters() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og
+            .chars()
+            .dedup_stable_bloom(64, 0.001, 0.001)
+            .collect::<String>();
+        assert_eq!(&dp, "spor dwjnychlite");
+    }
+}
@@ -0,0 +1,395 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+    mem,
+};
+
+use crate::{
+    fingerprint::Fingerprint,
+    hasher,
+    noncon::CapacityError,
+    store::{
+        self, DedupHashedStore, DedupHashedStoreAdapter, DedupStoreByKey, DedupStoreByKeyAdapter,
+        HashedStore, RemoveStore, SeenStore,
+    },
+};
+
+/* # cuckoo store */
+
+/// the number of fingerprints in a bucket
+const SLOTS: usize = 4;
+
+/// how many fingerprints are moved to their other bucket, before an insertion gives up
+const MAX_KICKS: usize = 500;
+
+/// the share of slots a filter is sized to fill, which cuckoo filters with four slots per bucket reach reliably
+const TARGET_LOAD: f64 = 0.95;
+
+/// salts the hash, which picks the bucket of an element, so that it is independent of the fingerprint
+const INDEX_SALT: u64 = 0x1319_8a2e_0370_7344;
+
+/// remembers elements approximately, as fingerprints of type `D` in a cuckoo filter, and can forget them again
+///
+/// every element has two candidate buckets, and a fingerprint is moved to its other bucket to make room for a new one;
+/// a new element is taken for a duplicate with probability about `8 / 2^b` for `b`-bit fingerprints,
+/// and removing an element, which was never inserted, may forget another one sharing its fingerprint
+#[derive(Debug, Clone)]
+pub struct CuckooStore<T, D = u16, S = RandomState> {
+    buckets: Vec<[D; SLOTS]>,
+    victim: Option<(usize, D)>,
+    hash_builder: S,
+    len: usize,
+    random: u64,
+    item: PhantomData<fn(&T)>,
+}
+
+impl<T, D> CuckooStore<T, D>
+where
+    D: Fingerprint + Default,
+{
+    /// sizes the filter for `capacity` elements, which then fill about 95% of the slots
+    pub fn new(capacity: usize) -> Self {
+        Self::with_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<T, D, S> CuckooStore<T, D, S>
+where
+    D: Fingerprint + Default,
+{
+    /// as `new`, but hashes the elements with provided `BuildHasher`
+    pub fn with_hasher(capacity: usize, hash_builder: S) -> Self {
+        let buckets = (capacity as f64 / TARGET_LOAD / SLOTS as f64).ceil() as usize;
+        Self {
+            buckets: vec![[D::default(); SLOTS]; buckets.max(1)],
+            victim: None,
+            hash_builder,
+            len: 0,
+            random: 0,
+            item: PhantomData,
+        }
+    }
+
+    /// the number of fingerprint slots
+    pub fn slots(&self) -> usize {
+        self.buckets.len() * SLOTS
+    }
+
+    /// the share of slots taken, leaving out a fingerprint left over by a failed insertion
+    pub fn load_factor(&self) -> f64 {
+        (self.len - usize::from(self.is_full())) as f64 / self.slots() as f64
+    }
+
+    /// checks whether an insertion has failed to find room, so that further new elements may not fit
+    pub fn is_full(&self) -> bool {
+        self.victim.is_some()
+    }
+
+    fn next_random(&mut self) -> u64 {
+        let random = hasher::splitmix(self.random);
+        self.random = self.random.wrapping_add(hasher::GAMMA);
+        random
+    }
+}
+
+impl<T, D, S> CuckooStore<T, D, S>
+where
+    T: Hash,
+    D: Fingerprint + Default,
+    S: BuildHasher,
+{
+    /// the fingerprint of `item`, which is never the empty one, and its first bucket
+    fn locate(&self, item: &T) -> (D, usize) {
+        let mut fingerprint = D::digest(item, &self.hash_builder);
+        let mut salt = 0_u64;
+        while fingerprint == D::default() {
+            salt += 1;
+            fingerprint = D::digest(&(salt, item), &self.hash_builder);
+        }
+        let index = self.hash_builder.hash_one((INDEX_SALT, item)) % self.buckets.len() as u64;
+        (fingerprint, index as usize)
+    }
+
+    /// the other bucket of a fingerprint, found from either of them
+    ///
+    /// the buckets sum up to a hash of the fingerprint, so the table can have any number of buckets
+    fn alternate(&self, index: usize, fingerprint: D) -> usize {
+        let len = self.buckets.len();
+        let sum = (self.hash_builder.hash_one(fingerprint) % len as u64) as usize;
+        (sum + len - index) % len
+    }
+
+    /// checks whether the fingerprint left over by a failed insertion belongs to either bucket
+    fn left_over(&self, index: usize, other: usize, fingerprint: D) -> bool {
+        match self.victim {
+            Some((left, stored)) => stored == fingerprint && (left == index || left == other),
+            None => false,
+        }
+    }
+
+    /// checks whether either bucket holds `fingerprint`
+    fn holds(&self, index: usize, other: usize, fingerprint: D) -> bool {
+        self.buckets[index].contains(&fingerprint)
+            || self.buckets[other].contains(&fingerprint)
+            || self.left_over(index, other, fingerprint)
+    }
+
+    fn put(&mut self, index: usize, fingerprint: D) -> bool {
+        match self.buckets[index]
+            .iter_mut()
+            .find(|slot| **slot == D::default())
+        {
+            Some(slot) => {
+                *slot = fingerprint;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// moves fingerprints to their other buckets until `fingerprint` fits, giving back the one left over if it does not
+    fn kick(&mut self, mut index: usize, mut fingerprint: D) -> Result<(), (usize, D)> {
+        for _ in 0..MAX_KICKS {
+            let slot = (self.next_random() % SLOTS as u64) as usize;
+            fingerprint = mem::replace(&mut self.buckets[index][slot], fingerprint);
+            index = self.alternate(index, fingerprint);
+            if self.put(index, fingerprint) {
+                return Ok(());
+            }
+        }
+        Err((index, fingerprint))
+    }
+
+    /// records `item` unless it was recorded before, or gives it back if the filter is full
+    pub fn try_insert(&mut self, item: T) -> Result<bool, CapacityError<T>> {
+        self.try_record(&item).ok_or(CapacityError(item))
+    }
+
+    /// records `item` unless it was recorded before, or gives `None` if the filter is full
+    fn try_record(&mut self, item: &T) -> Option<bool> {
+        let (fingerprint, index) = self.locate(item);
+        let other = self.alternate(index, fingerprint);
+        if self.holds(index, other, fingerprint) {
+            return Some(false);
+        }
+        if !self.put(index, fingerprint) && !self.put(other, fingerprint) {
+            if self.is_full() {
+                return None;
+            }
+            let start = if self.next_random() & 1 == 0 {
+                index
+            } else {
+                other
+            };
+            self.victim = self.kick(start, fingerprint).err();
+        }
+        self.len += 1;
+        Some(true)
+    }
+}
+
+impl<T, D, S> SeenStore<T> for CuckooStore<T, D, S>
+where
+    T: Hash,
+    D: Fingerprint + Default,
+    S: BuildHasher,
+{
+    fn contains(&self, item: &T) -> bool {
+        let (fingerprint, index) = self.locate(item);
+        self.holds(index, self.alternate(index, fingerprint), fingerprint)
+    }
+
+    /// records `item`, letting it through without recording it if the filter is full
+    fn insert(&mut self, item: T) -> bool {
+        self.try_record(&item).unwrap_or(true)
+    }
+
+    fn record(&mut self, item: T) -> bool {
+        self.try_record(&item).unwrap_or(true)
+    }
+
+    fn record_ref(&mut self, item: &T) -> bool {
+        self.record_hashed(item)
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        self.buckets
+            .iter_mut()
+            .for_each(|bucket| *bucket = [D::default(); SLOTS]);
+        self.victim = None;
+        self.len = 0;
+    }
+}
+
+impl<T, D, S> HashedStore<T> for CuckooStore<T, D, S>
+where
+    T: Hash,
+    D: Fingerprint + Default,
+    S: BuildHasher,
+{
+    /// records `item` from its fingerprint alone, which is computed once
+    fn record_hashed(&mut self, item: &T) -> bool {
+        self.try_record(item).unwrap_or(true)
+    }
+}
+
+impl<T, D, S> RemoveStore<T> for CuckooStore<T, D, S>
+where
+    T: Hash,
+    D: Fingerprint + Default,
+    S: BuildHasher,
+{
+    fn remove(&mut self, item: &T) -> bool {
+        let (fingerprint, index) = self.locate(item);
+        let other = self.alternate(index, fingerprint);
+        if self.left_over(index, other, fingerprint) {
+            self.victim = None;
+            self.len -= 1;
+            return true;
+        }
+        let slot = [index, other].into_iter().find_map(|bucket| {
+            self.buckets[bucket]
+                .iter()
+                .position(|slot| *slot == fingerprint)
+                .map(|slot| (bucket, slot))
+        });
+        let Some((bucket, slot)) = slot else {
+            return false;
+        };
+        self.buckets[bucket][slot] = D::default();
+        self.len -= 1;
+        if let Some((left, stored)) = self.victim.take() {
+            if !self.put(left, stored) {
+                self.victim = self.kick(left, stored).err();
+            }
+        }
+        true
+    }
+}
+
+/* # deduplicator */
+
+/// removes equal elements pushed one at a time, remembering them in a cuckoo filter, which can forget them again
+pub type Deduplicator<T, D = u16, S = RandomState> = store::Deduplicator<T, CuckooStore<T, D, S>>;
+
+/* # dedup cuckoo */
+
+/// removes equal elements, remembering them in a cuckoo filter, which drops some new ones as well
+pub type DedupCuckoo<I, D = u16, S = RandomState> =
+    DedupHashedStore<I, CuckooStore<<I as Iterator>::Item, D, S>>;
+
+/// provides the `dedup_cuckoo` method on `Iterator`s
+pub trait DedupCuckooAdapter: Iterator {
+    fn dedup_cuckoo<D>(self, capacity: usize) -> DedupCuckoo<Self, D>
+    where
+        Self: Sized,
+        D: Fingerprint + Default,
+    {
+        self.dedup_hashed_store(CuckooStore::new(capacity))
+    }
+}
+
+impl<I> DedupCuckooAdapter for I where I: Iterator {}
+
+/* # dedup cuckoo by key */
+
+/// removes elements, which give equal outputs from provided function, remembering the outputs in a cuckoo filter
+pub type DedupCuckooByKey<I, F, K, D = u16, S = RandomState> =
+    DedupStoreByKey<I, CuckooStore<K, D, S>, F, K>;
+
+/// provides the `dedup_cuckoo_by_key` method on `Iterator`s
+pub trait DedupCuckooByKeyAdapter<F, K>: Iterator {
+    fn dedup_cuckoo_by_key<D>(self, capacity: usize, function: F) -> DedupCuckooByKey<Self, F, K, D>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+        D: Fingerprint + Default,
+    {
+        self.dedup_store_by_key(CuckooStore::new(capacity), function)
+    }
+}
+
+impl<I, F, K> DedupCuckooByKeyAdapter<F, K> for I where I: Iterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hasher::FastState;
+
+    #[test]
+    fn deduplicate_empty_iterator() {
+        let og = Vec::<u8>::new();
+        let dp = og.iter().dedup_cuckoo::<u16>(16).count();
+        assert_eq!(dp, 0);
+    }
+
+    #[test]
+    fn remove_duplicate_character() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og.chars().dedup_cuckoo::<u32>(64).collect::<String>();
+        assert_eq!(&dp, "spor dwjnychlite");
+    }
+
+    #[test]
+    fn elements_need_not_be_clone() {
+        #[derive(Debug, Hash)]
+        struct Page(&'static str);
+
+        let og = ["strona", "podstrona", "strona", "witryna"].map(Page);
+        let dp = og.into_iter().dedup_cuckoo::<u32>(16);
+        assert_eq!(
+            dp.map(|page| page.0).collect::<Vec<_>>(),
+            ["strona", "podstrona", "witryna"]
+        );
+    }
+
+    #[test]
+    fn removed_element_is_emitted_again() {
+        let mut dd = Deduplicator::<_, u16>::with_store(CuckooStore::new(16));
+        let orders = ["zamówienie 1", "zamówienie 2", "zamówienie 1"];
+        let dp = orders.iter().filter(|&&order| dd.insert(order)).count();
+        assert_eq!(dp, 2);
+        assert!(dd.remove(&"zamówienie 1"));
+        assert!(!dd.remove(&"zamówienie 3"));
+        assert!(dd.would_emit(&"zamówienie 1"));
+        assert!(dd.insert("zamówienie 1"));
+        assert_eq!(dd.len(), 2);
+    }
+
+    #[test]
+    fn fills_to_target_load() {
+        let mut store = CuckooStore::<u32, u16, _>::with_hasher(10_000, FastState::new());
+        let new = (0..10_000).filter(|&n| store.insert(n)).count();
+        assert!(!store.is_full());
+        assert!(new > 9_800);
+        assert!(store.load_factor() > 0.93);
+        assert!((0..10_000).all(|n| store.contains(&n)));
+        let found = (10_000..20_000).filter(|n| store.contains(n)).count();
+        assert!(found < 100);
+    }
+
+    #[test]
+    fn full_filter_refuses_and_recovers() {
+        let mut store = CuckooStore::<u32, u32, _>::with_hasher(8, FastState::new());
+        let slots = store.slots() as u32;
+        let refused = (0..2 * slots)
+            .filter(|&n| store.try_insert(n).is_err())
+            .collect::<Vec<_>>();
+        assert!(store.is_full());
+        assert!(!refused.is_empty());
+        assert!(store.load_factor() > 0.9);
+        assert!(store.load_factor() <= 1.0);
+        let kept = (0..2 * slots)
+            .filter(|n| !refused.contains(n))
+            .collect::<Vec<_>>();
+        assert!(kept.iter().all(|n| store.contains(n)));
+        kept.iter().for_each(|n| assert!(store.remove(n)));
+        assert!(store.is_empty());
+        assert!(!store.is_full());
+    }
+}
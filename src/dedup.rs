@@ -1,165 +1,1473 @@
+use alloc::{vec, vec::Vec};
+use core::iter::{Fuse, FusedIterator};
+
+/* # keep policy */
+
+/// chooses which element of a run of consecutive equal elements is yielded
+pub trait Keep<T> {
+    /// checks whether `candidate`, which comes later in the run, should replace the `kept` element
+    fn replace(&mut self, kept: &T, candidate: &T) -> bool;
+}
+
+/// keeps the first element of every run
+#[derive(Debug, Clone, Copy, Default)]
+pub struct First;
+
+impl<T> Keep<T> for First {
+    fn replace(&mut self, _kept: &T, _candidate: &T) -> bool {
+        false
+    }
+}
+
+/// keeps the last element of every run
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Last;
+
+impl<T> Keep<T> for Last {
+    fn replace(&mut self, _kept: &T, _candidate: &T) -> bool {
+        true
+    }
+}
+
+/// keeps the first element of every run, which gives the smallest output from provided function
+#[derive(Debug, Clone, Copy)]
+pub struct MinByKey<F>(pub F);
+
+impl<T, F, K> Keep<T> for MinByKey<F>
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    fn replace(&mut self, kept: &T, candidate: &T) -> bool {
+        (self.0)(candidate) < (self.0)(kept)
+    }
+}
+
+/// keeps the first element of every run, which gives the largest output from provided function
+#[derive(Debug, Clone, Copy)]
+pub struct MaxByKey<F>(pub F);
+
+impl<T, F, K> Keep<T> for MaxByKey<F>
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    fn replace(&mut self, kept: &T, candidate: &T) -> bool {
+        (self.0)(candidate) > (self.0)(kept)
+    }
+}
+
+/// a custom selector, which is asked whether the later element should replace the kept one
+impl<T, F> Keep<T> for F
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    fn replace(&mut self, kept: &T, candidate: &T) -> bool {
+        self(kept, candidate)
+    }
+}
+
+/// a policy, which chooses the same element whichever end a run is read from, so that runs can be yielded from the back
+///
+/// custom selectors are not, as they need not be a consistent order on the elements of a run
+pub trait Reversible<T>: Keep<T> {}
+
+impl<T> Reversible<T> for First {}
+
+impl<T> Reversible<T> for Last {}
+
+impl<T, F, K> Reversible<T> for MinByKey<F>
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+}
+
+impl<T, F, K> Reversible<T> for MaxByKey<F>
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+}
+
+/// asks the policy about a run read from the back, where the candidate comes earlier than the kept element
+struct Reversed<'a, P>(&'a mut P);
+
+impl<T, P> Keep<T> for Reversed<'_, P>
+where
+    P: Keep<T>,
+{
+    fn replace(&mut self, kept: &T, candidate: &T) -> bool {
+        !self.0.replace(candidate, kept)
+    }
+}
+
+/* # comparison mode */
+
+/// chooses which element of a run every new element is compared with
+pub trait Compare {
+    /// whether new elements are compared with the first element of the run
+    const ANCHORED: bool;
+}
+
+/// compares every new element with the previous one, so a run may drift arbitrarily far from its first element
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Previous;
+
+impl Compare for Previous {
+    const ANCHORED: bool = false;
+}
+
+/// compares every new element with the first element of the run
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Anchor;
+
+impl Compare for Anchor {
+    const ANCHORED: bool = true;
+}
+
+/// a run of consecutive equal elements, folded as it is read
+///
+/// `anchor` and `last` are only held when they differ from `kept`
+struct Run<T> {
+    kept: T,
+    anchor: Option<T>,
+    last: Option<T>,
+}
+
+impl<T> Run<T> {
+    fn new(first: T) -> Self {
+        Self {
+            kept: first,
+            anchor: None,
+            last: None,
+        }
+    }
+
+    /// the element of the run, which the next element is compared with
+    fn reference(&self, anchored: bool) -> &T {
+        match anchored {
+            true => self.anchor.as_ref(),
+            false => self.last.as_ref(),
+        }
+        .unwrap_or(&self.kept)
+    }
+
+    fn push<P>(&mut self, keep: &mut P, next: T, anchored: bool)
+    where
+        P: Keep<T>,
+    {
+        match keep.replace(&self.kept, &next) {
+            true => {
+                let previous = core::mem::replace(&mut self.kept, next);
+                if anchored && self.anchor.is_none() {
+                    self.anchor = Some(previous);
+                }
+                self.last = None;
+            }
+            false => {
+                if !anchored {
+                    self.last = Some(next);
+                }
+            }
+        }
+    }
+}
+
+/// bounds the number of runs left, given the inner iterator and the number of elements held at both ends
+fn size_hint(inner: (usize, Option<usize>), pending: usize) -> (usize, Option<usize>) {
+    let (lower, upper) = inner;
+    (
+        usize::from(lower > 0 || pending > 0),
+        upper.and_then(|upper| upper.checked_add(pending)),
+    )
+}
+
 /* # dedup */
 
-/// removes consecutive equal elements
+/// removes consecutive equal elements
+#[derive(Debug, Clone)]
+pub struct Dedup<I, P = Last>
+where
+    I: Iterator,
+{
+    iterator: Fuse<I>,
+    front: Option<I::Item>,
+    back: Option<I::Item>,
+    keep: P,
+}
+
+impl<I, P> Dedup<I, P>
+where
+    I: Iterator,
+{
+    /// chooses which element of every run is yielded
+    pub fn keep<Q>(self, keep: Q) -> Dedup<I, Q>
+    where
+        Q: Keep<I::Item>,
+    {
+        Dedup {
+            iterator: self.iterator,
+            front: self.front,
+            back: self.back,
+            keep,
+        }
+    }
+
+    /// keeps the later element of a run, whenever provided function returns `true` for the kept and the later element
+    ///
+    /// runs kept this way can only be yielded from the front, see `Reversible`
+    pub fn keep_by<F>(self, selector: F) -> Dedup<I, F>
+    where
+        F: FnMut(&I::Item, &I::Item) -> bool,
+    {
+        self.keep(selector)
+    }
+}
+
+impl<I, P> Iterator for Dedup<I, P>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+    P: Keep<I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let first = self
+            .front
+            .take()
+            .or_else(|| self.iterator.next())
+            .or_else(|| self.back.take())?;
+        let mut run = Run::new(first);
+        while let Some(next) = self.iterator.next().or_else(|| self.back.take()) {
+            if run.reference(false) != &next {
+                self.front = Some(next);
+                break;
+            }
+            run.push(&mut self.keep, next, false);
+        }
+        Some(run.kept)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        size_hint(
+            self.iterator.size_hint(),
+            usize::from(self.front.is_some()) + usize::from(self.back.is_some()),
+        )
+    }
+}
+
+impl<I, P> DoubleEndedIterator for Dedup<I, P>
+where
+    I: DoubleEndedIterator,
+    I::Item: PartialEq,
+    P: Reversible<I::Item>,
+{
+    fn next_back(&mut self) -> Option<I::Item> {
+        let last = self
+            .back
+            .take()
+            .or_else(|| self.iterator.next_back())
+            .or_else(|| self.front.take())?;
+        let mut run = Run::new(last);
+        let mut keep = Reversed(&mut self.keep);
+        while let Some(previous) = self.iterator.next_back().or_else(|| self.front.take()) {
+            if &previous != run.reference(false) {
+                self.back = Some(previous);
+                break;
+            }
+            run.push(&mut keep, previous, false);
+        }
+        Some(run.kept)
+    }
+}
+
+impl<I, P> FusedIterator for Dedup<I, P>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+    P: Keep<I::Item>,
+{
+}
+
+/// provides the `dedup` method on `Iterator`s
+pub trait DedupAdapter: Iterator {
+    fn dedup(self) -> Dedup<Self>
+    where
+        Self: Sized,
+    {
+        Dedup {
+            iterator: self.fuse(),
+            front: None,
+            back: None,
+            keep: Last,
+        }
+    }
+}
+
+impl<I> DedupAdapter for I where I: Iterator {}
+
+/* # dedup by */
+
+/// removes consecutive elements, whose equality is asserted by provided function
+///
+/// by default every element is compared with the previous one, see `anchored` for comparing with the first one
+#[derive(Debug, Clone)]
+pub struct DedupBy<I, F, P = Last, M = Previous>
+where
+    I: Iterator,
+{
+    iterator: Fuse<I>,
+    front: Option<I::Item>,
+    back: Option<I::Item>,
+    equivalence: F,
+    keep: P,
+    compare: M,
+}
+
+impl<I, F, P, M> DedupBy<I, F, P, M>
+where
+    I: Iterator,
+{
+    /// chooses which element of every run is yielded
+    pub fn keep<Q>(self, keep: Q) -> DedupBy<I, F, Q, M>
+    where
+        Q: Keep<I::Item>,
+    {
+        DedupBy {
+            iterator: self.iterator,
+            front: self.front,
+            back: self.back,
+            equivalence: self.equivalence,
+            keep,
+            compare: self.compare,
+        }
+    }
+
+    /// keeps the later element of a run, whenever provided function returns `true` for the kept and the later element
+    ///
+    /// runs kept this way can only be yielded from the front, see `Reversible`
+    pub fn keep_by<G>(self, selector: G) -> DedupBy<I, F, G, M>
+    where
+        G: FnMut(&I::Item, &I::Item) -> bool,
+    {
+        self.keep(selector)
+    }
+
+    /// compares every new element with the first element of the run instead of the previous one
+    ///
+    /// with a non-transitive equivalence, such as a tolerance, this bounds how far a run can drift;
+    /// runs then depend on the direction they are read in, so anchored adapters are not double-ended
+    pub fn anchored(self) -> DedupBy<I, F, P, Anchor> {
+        DedupBy {
+            iterator: self.iterator,
+            front: self.front,
+            back: self.back,
+            equivalence: self.equivalence,
+            keep: self.keep,
+            compare: Anchor,
+        }
+    }
+}
+
+impl<I, F, P, M> Iterator for DedupBy<I, F, P, M>
+where
+    I: Iterator,
+    F: Fn(&I::Item, &I::Item) -> bool,
+    P: Keep<I::Item>,
+    M: Compare,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let first = self
+            .front
+            .take()
+            .or_else(|| self.iterator.next())
+            .or_else(|| self.back.take())?;
+        let mut run = Run::new(first);
+        while let Some(next) = self.iterator.next().or_else(|| self.back.take()) {
+            if !(self.equivalence)(run.reference(M::ANCHORED), &next) {
+                self.front = Some(next);
+                break;
+            }
+            run.push(&mut self.keep, next, M::ANCHORED);
+        }
+        Some(run.kept)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        size_hint(
+            self.iterator.size_hint(),
+            usize::from(self.front.is_some()) + usize::from(self.back.is_some()),
+        )
+    }
+}
+
+impl<I, F, P> DoubleEndedIterator for DedupBy<I, F, P, Previous>
+where
+    I: DoubleEndedIterator,
+    F: Fn(&I::Item, &I::Item) -> bool,
+    P: Reversible<I::Item>,
+{
+    fn next_back(&mut self) -> Option<I::Item> {
+        let last = self
+            .back
+            .take()
+            .or_else(|| self.iterator.next_back())
+            .or_else(|| self.front.take())?;
+        let mut run = Run::new(last);
+        let mut keep = Reversed(&mut self.keep);
+        while let Some(previous) = self.iterator.next_back().or_else(|| self.front.take()) {
+            if !(self.equivalence)(&previous, run.reference(false)) {
+                self.back = Some(previous);
+                break;
+            }
+            run.push(&mut keep, previous, false);
+        }
+        Some(run.kept)
+    }
+}
+
+impl<I, F, P, M> FusedIterator for DedupBy<I, F, P, M>
+where
+    I: Iterator,
+    F: Fn(&I::Item, &I::Item) -> bool,
+    P: Keep<I::Item>,
+    M: Compare,
+{
+}
+
+/// provides the `dedup_by` method on `Iterator`s
+pub trait DedupByAdapter<F>: Iterator {
+    fn dedup_by(self, equivalence: F) -> DedupBy<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item, &Self::Item) -> bool,
+    {
+        DedupBy {
+            iterator: self.fuse(),
+            front: None,
+            back: None,
+            equivalence,
+            keep: Last,
+            compare: Previous,
+        }
+    }
+}
+
+impl<I, F> DedupByAdapter<F> for I where I: Iterator {}
+
+/* # dedup by key */
+
+/// removes consecutive elements, which give equal outputs from provided function
+///
+/// the output is computed once per element and kept alongside it for the rest of the run
+#[derive(Debug, Clone)]
+pub struct DedupByKey<I, F, K, P = Last>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+{
+    iterator: Fuse<I>,
+    front: Option<(I::Item, K)>,
+    back: Option<(I::Item, K)>,
+    function: F,
+    keep: P,
+}
+
+impl<I, F, K, P> DedupByKey<I, F, K, P>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+{
+    /// chooses which element of every run is yielded
+    pub fn keep<Q>(self, keep: Q) -> DedupByKey<I, F, K, Q>
+    where
+        Q: Keep<I::Item>,
+    {
+        DedupByKey {
+            iterator: self.iterator,
+            front: self.front,
+            back: self.back,
+            function: self.function,
+            keep,
+        }
+    }
+
+    /// keeps the later element of a run, whenever provided function returns `true` for the kept and the later element
+    ///
+    /// runs kept this way can only be yielded from the front, see `Reversible`
+    pub fn keep_by<G>(self, selector: G) -> DedupByKey<I, F, K, G>
+    where
+        G: FnMut(&I::Item, &I::Item) -> bool,
+    {
+        self.keep(selector)
+    }
+}
+
+impl<I, F, K, P> Iterator for DedupByKey<I, F, K, P>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+    K: PartialEq,
+    P: Keep<I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let function = &self.function;
+        let keyed = |item| {
+            let key = function(&item);
+            (item, key)
+        };
+        let (mut kept, mut key) = self
+            .front
+            .take()
+            .or_else(|| self.iterator.next().map(keyed))
+            .or_else(|| self.back.take())?;
+        while let Some((next, next_key)) =
+            self.iterator.next().map(keyed).or_else(|| self.back.take())
+        {
+            if next_key != key {
+                self.front = Some((next, next_key));
+                break;
+            }
+            if self.keep.replace(&kept, &next) {
+                kept = next;
+            }
+            key = next_key;
+        }
+        Some(kept)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        size_hint(
+            self.iterator.size_hint(),
+            usize::from(self.front.is_some()) + usize::from(self.back.is_some()),
+        )
+    }
+}
+
+impl<I, F, K, P> DoubleEndedIterator for DedupByKey<I, F, K, P>
+where
+    I: DoubleEndedIterator,
+    F: Fn(&I::Item) -> K,
+    K: PartialEq,
+    P: Reversible<I::Item>,
+{
+    fn next_back(&mut self) -> Option<I::Item> {
+        let function = &self.function;
+        let keyed = |item| {
+            let key = function(&item);
+            (item, key)
+        };
+        let (mut kept, mut key) = self
+            .back
+            .take()
+            .or_else(|| self.iterator.next_back().map(keyed))
+            .or_else(|| self.front.take())?;
+        let mut keep = Reversed(&mut self.keep);
+        while let Some((previous, previous_key)) = self
+            .iterator
+            .next_back()
+            .map(keyed)
+            .or_else(|| self.front.take())
+        {
+            if previous_key != key {
+                self.back = Some((previous, previous_key));
+                break;
+            }
+            if keep.replace(&kept, &previous) {
+                kept = previous;
+            }
+            key = previous_key;
+        }
+        Some(kept)
+    }
+}
+
+impl<I, F, K, P> FusedIterator for DedupByKey<I, F, K, P>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+    K: PartialEq,
+    P: Keep<I::Item>,
+{
+}
+
+/// Provides the `dedup_by_key` method on `Iterator`s.
+pub trait DedupByKeyAdapter<F, K>: Iterator {
+    fn dedup_by_key(self, function: F) -> DedupByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        DedupByKey {
+            iterator: self.fuse(),
+            front: None,
+            back: None,
+            function,
+            keep: Last,
+        }
+    }
+}
+
+impl<I, F, K> DedupByKeyAdapter<F, K> for I where I: Iterator {}
+
+/* # dedup by key ref */
+
+/// removes consecutive elements, which give equal outputs from provided function, borrowing from the element
+///
+/// borrowed outputs are cheap to recompute, so they are not kept alongside the elements
+#[derive(Debug, Clone)]
+pub struct DedupByKeyRef<I, F, K, P = Last>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> &K,
+    K: ?Sized,
+{
+    iterator: Fuse<I>,
+    front: Option<I::Item>,
+    back: Option<I::Item>,
+    function: F,
+    keep: P,
+}
+
+impl<I, F, K, P> DedupByKeyRef<I, F, K, P>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> &K,
+    K: ?Sized,
+{
+    /// chooses which element of every run is yielded
+    pub fn keep<Q>(self, keep: Q) -> DedupByKeyRef<I, F, K, Q>
+    where
+        Q: Keep<I::Item>,
+    {
+        DedupByKeyRef {
+            iterator: self.iterator,
+            front: self.front,
+            back: self.back,
+            function: self.function,
+            keep,
+        }
+    }
+
+    /// keeps the later element of a run, whenever provided function returns `true` for the kept and the later element
+    ///
+    /// runs kept this way can only be yielded from the front, see `Reversible`
+    pub fn keep_by<G>(self, selector: G) -> DedupByKeyRef<I, F, K, G>
+    where
+        G: FnMut(&I::Item, &I::Item) -> bool,
+    {
+        self.keep(selector)
+    }
+}
+
+impl<I, F, K, P> Iterator for DedupByKeyRef<I, F, K, P>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> &K,
+    K: PartialEq + ?Sized,
+    P: Keep<I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let first = self
+            .front
+            .take()
+            .or_else(|| self.iterator.next())
+            .or_else(|| self.back.take())?;
+        let mut run = Run::new(first);
+        while let Some(next) = self.iterator.next().or_else(|| self.back.take()) {
+            if (self.function)(run.reference(false)) != (self.function)(&next) {
+                self.front = Some(next);
+                break;
+            }
+            run.push(&mut self.keep, next, false);
+        }
+        Some(run.kept)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        size_hint(
+            self.iterator.size_hint(),
+            usize::from(self.front.is_some()) + usize::from(self.back.is_some()),
+        )
+    }
+}
+
+impl<I, F, K, P> DoubleEndedIterator for DedupByKeyRef<I, F, K, P>
+where
+    I: DoubleEndedIterator,
+    F: Fn(&I::Item) -> &K,
+    K: PartialEq + ?Sized,
+    P: Reversible<I::Item>,
+{
+    fn next_back(&mut self) -> Option<I::Item> {
+        let last = self
+            .back
+            .take()
+            .or_else(|| self.iterator.next_back())
+            .or_else(|| self.front.take())?;
+        let mut run = Run::new(last);
+        let mut keep = Reversed(&mut self.keep);
+        while let Some(previous) = self.iterator.next_back().or_else(|| self.front.take()) {
+            if (self.function)(&previous) != (self.function)(run.reference(false)) {
+                self.back = Some(previous);
+                break;
+            }
+            run.push(&mut keep, previous, false);
+        }
+        Some(run.kept)
+    }
+}
+
+impl<I, F, K, P> FusedIterator for DedupByKeyRef<I, F, K, P>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> &K,
+    K: PartialEq + ?Sized,
+    P: Keep<I::Item>,
+{
+}
+
+/// provides the `dedup_by_key_ref` method on `Iterator`s
+pub trait DedupByKeyRefAdapter<F, K>: Iterator
+where
+    K: ?Sized,
+{
+    fn dedup_by_key_ref(self, function: F) -> DedupByKeyRef<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> &K,
+    {
+        DedupByKeyRef {
+            iterator: self.fuse(),
+            front: None,
+            back: None,
+            function,
+            keep: Last,
+        }
+    }
+}
+
+impl<I, F, K> DedupByKeyRefAdapter<F, K> for I
+where
+    I: Iterator,
+    K: ?Sized,
+{
+}
+
+/* # try dedup */
+
+/// removes consecutive equal `Ok` elements, and passes errors through in order
+///
+/// an error ends the run before it, see `short_circuit` for stopping at the first one
+#[derive(Debug, Clone)]
+pub struct TryDedup<I>
+where
+    I: Iterator,
+{
+    iterator: Fuse<I>,
+    front: Option<I::Item>,
+    short_circuit: bool,
+    failed: bool,
+}
+
+impl<I> TryDedup<I>
+where
+    I: Iterator,
+{
+    /// stops iterating after yielding the first error
+    pub fn short_circuit(self) -> Self {
+        Self {
+            short_circuit: true,
+            ..self
+        }
+    }
+}
+
+impl<I, T, E> Iterator for TryDedup<I>
+where
+    I: Iterator<Item = Result<T, E>>,
+    T: PartialEq,
+{
+    type Item = Result<T, E>;
+
+    fn next(&mut self) -> Option<Result<T, E>> {
+        if self.failed {
+            return None;
+        }
+        let mut kept = match self.front.take().or_else(|| self.iterator.next())? {
+            Ok(item) => item,
+            Err(error) => {
+                self.failed = self.short_circuit;
+                return Some(Err(error));
+            }
+        };
+        for next in self.iterator.by_ref() {
+            match next {
+                Ok(item) if item == kept => kept = item,
+                next => {
+                    self.front = Some(next);
+                    break;
+                }
+            }
+        }
+        Some(Ok(kept))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.failed {
+            return (0, Some(0));
+        }
+        size_hint(self.iterator.size_hint(), usize::from(self.front.is_some()))
+    }
+}
+
+impl<I, T, E> FusedIterator for TryDedup<I>
+where
+    I: Iterator<Item = Result<T, E>>,
+    T: PartialEq,
+{
+}
+
+/// provides the `try_dedup` method on `Iterator`s of `Result`s
+pub trait TryDedupAdapter<T, E>: Iterator<Item = Result<T, E>> {
+    fn try_dedup(self) -> TryDedup<Self>
+    where
+        Self: Sized,
+    {
+        TryDedup {
+            iterator: self.fuse(),
+            front: None,
+            short_circuit: false,
+            failed: false,
+        }
+    }
+}
+
+impl<I, T, E> TryDedupAdapter<T, E> for I where I: Iterator<Item = Result<T, E>> {}
+
+/* # try dedup by key */
+
+/// removes consecutive `Ok` elements, which give equal outputs from provided fallible function,
+/// and passes errors of both the elements and the function through in order
+#[derive(Debug, Clone)]
+pub struct TryDedupByKey<I, F, K>
+where
+    I: Iterator,
+{
+    iterator: Fuse<I>,
+    front: Option<I::Item>,
+    front_key: Option<K>,
+    function: F,
+    short_circuit: bool,
+    failed: bool,
+}
+
+impl<I, F, K> TryDedupByKey<I, F, K>
+where
+    I: Iterator,
+{
+    /// stops iterating after yielding the first error
+    pub fn short_circuit(self) -> Self {
+        Self {
+            short_circuit: true,
+            ..self
+        }
+    }
+}
+
+/// pairs an `Ok` element with its output, computing the output unless it is already known
+fn try_keyed<T, E, F, K>(function: &F, item: Result<T, E>, key: Option<K>) -> Result<(T, K), E>
+where
+    F: Fn(&T) -> Result<K, E>,
+{
+    let item = item?;
+    let key = match key {
+        Some(key) => key,
+        None => function(&item)?,
+    };
+    Ok((item, key))
+}
+
+impl<I, F, T, E, K> Iterator for TryDedupByKey<I, F, K>
+where
+    I: Iterator<Item = Result<T, E>>,
+    F: Fn(&T) -> Result<K, E>,
+    K: PartialEq,
+{
+    type Item = Result<T, E>;
+
+    fn next(&mut self) -> Option<Result<T, E>> {
+        if self.failed {
+            return None;
+        }
+        let first = self.front.take().or_else(|| self.iterator.next())?;
+        let (mut kept, key) = match try_keyed(&self.function, first, self.front_key.take()) {
+            Ok(keyed) => keyed,
+            Err(error) => {
+                self.failed = self.short_circuit;
+                return Some(Err(error));
+            }
+        };
+        for next in self.iterator.by_ref() {
+            match try_keyed(&self.function, next, None) {
+                Ok((item, next_key)) if next_key == key => kept = item,
+                Ok((item, next_key)) => {
+                    self.front = Some(Ok(item));
+                    self.front_key = Some(next_key);
+                    break;
+                }
+                Err(error) => {
+                    self.front = Some(Err(error));
+                    break;
+                }
+            }
+        }
+        Some(Ok(kept))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.failed {
+            return (0, Some(0));
+        }
+        size_hint(self.iterator.size_hint(), usize::from(self.front.is_some()))
+    }
+}
+
+impl<I, F, T, E, K> FusedIterator for TryDedupByKey<I, F, K>
+where
+    I: Iterator<Item = Result<T, E>>,
+    F: Fn(&T) -> Result<K, E>,
+    K: PartialEq,
+{
+}
+
+/// provides the `try_dedup_by_key` method on `Iterator`s of `Result`s
+pub trait TryDedupByKeyAdapter<T, E, F, K>: Iterator<Item = Result<T, E>> {
+    fn try_dedup_by_key(self, function: F) -> TryDedupByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&T) -> Result<K, E>,
+    {
+        TryDedupByKey {
+            iterator: self.fuse(),
+            front: None,
+            front_key: None,
+            function,
+            short_circuit: false,
+            failed: false,
+        }
+    }
+}
+
+impl<I, T, E, F, K> TryDedupByKeyAdapter<T, E, F, K> for I where I: Iterator<Item = Result<T, E>> {}
+
+/* # unique only */
+
+/// yields only elements, which are not equal to their neighbours, i.e. drops every run longer than one
+#[derive(Debug, Clone)]
+pub struct UniqueOnly<I>
+where
+    I: Iterator,
+{
+    iterator: Fuse<I>,
+    front: Option<I::Item>,
+}
+
+impl<I> Iterator for UniqueOnly<I>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        loop {
+            let first = self.front.take().or_else(|| self.iterator.next())?;
+            let mut single = true;
+            for next in self.iterator.by_ref() {
+                if next != first {
+                    self.front = Some(next);
+                    break;
+                }
+                single = false;
+            }
+            if single {
+                return Some(first);
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, upper) = size_hint(self.iterator.size_hint(), usize::from(self.front.is_some()));
+        (0, upper)
+    }
+}
+
+impl<I> FusedIterator for UniqueOnly<I>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+{
+}
+
+/// provides the `unique_only` method on `Iterator`s
+pub trait UniqueOnlyAdapter: Iterator {
+    fn unique_only(self) -> UniqueOnly<Self>
+    where
+        Self: Sized,
+    {
+        UniqueOnly {
+            iterator: self.fuse(),
+            front: None,
+        }
+    }
+}
+
+impl<I> UniqueOnlyAdapter for I where I: Iterator {}
+
+/* # unique only by key */
+
+/// yields only elements, which give an output from provided function different from their neighbours
+///
+/// the output is computed once per element and kept alongside it for the rest of the run
+#[derive(Debug, Clone)]
+pub struct UniqueOnlyByKey<I, F, K>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+{
+    iterator: Fuse<I>,
+    front: Option<(I::Item, K)>,
+    function: F,
+}
+
+impl<I, F, K> Iterator for UniqueOnlyByKey<I, F, K>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+    K: PartialEq,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let function = &self.function;
+        let keyed = |item| {
+            let key = function(&item);
+            (item, key)
+        };
+        loop {
+            let (first, key) = self
+                .front
+                .take()
+                .or_else(|| self.iterator.next().map(keyed))?;
+            let mut single = true;
+            for (next, next_key) in self.iterator.by_ref().map(keyed) {
+                if next_key != key {
+                    self.front = Some((next, next_key));
+                    break;
+                }
+                single = false;
+            }
+            if single {
+                return Some(first);
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, upper) = size_hint(self.iterator.size_hint(), usize::from(self.front.is_some()));
+        (0, upper)
+    }
+}
+
+impl<I, F, K> FusedIterator for UniqueOnlyByKey<I, F, K>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+    K: PartialEq,
+{
+}
+
+/// provides the `unique_only_by_key` method on `Iterator`s
+pub trait UniqueOnlyByKeyAdapter<F, K>: Iterator {
+    fn unique_only_by_key(self, function: F) -> UniqueOnlyByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        UniqueOnlyByKey {
+            iterator: self.fuse(),
+            front: None,
+            function,
+        }
+    }
+}
+
+impl<I, F, K> UniqueOnlyByKeyAdapter<F, K> for I where I: Iterator {}
+
+/* # runs */
+
+/// a run of consecutive equal elements, with the positions of its first and last element in the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span<T> {
+    /// the kept element, or all elements of the run when collected with `elements`
+    pub item: T,
+    /// the number of elements in the run
+    pub len: usize,
+    /// the index of the first element of the run in the input
+    pub start: usize,
+    /// the index of the last element of the run in the input, so inclusive, and equal to `start` for a single element
+    pub end: usize,
+}
+
+impl<T> Span<T> {
+    fn new(item: T, start: usize, len: usize) -> Self {
+        Self {
+            item,
+            len,
+            start,
+            end: start + len - 1,
+        }
+    }
+}
+
+/// groups consecutive equal elements into runs, yielding the kept element of every run with its span
+#[derive(Debug, Clone)]
+pub struct Runs<I, P = Last>
+where
+    I: Iterator,
+{
+    iterator: Fuse<I>,
+    front: Option<I::Item>,
+    position: usize,
+    keep: P,
+}
+
+impl<I, P> Runs<I, P>
+where
+    I: Iterator,
+{
+    /// chooses which element of every run is yielded
+    pub fn keep<Q>(self, keep: Q) -> Runs<I, Q>
+    where
+        Q: Keep<I::Item>,
+    {
+        Runs {
+            iterator: self.iterator,
+            front: self.front,
+            position: self.position,
+            keep,
+        }
+    }
+
+    /// keeps the later element of a run, whenever provided function returns `true` for the kept and the later element
+    pub fn keep_by<F>(self, selector: F) -> Runs<I, F>
+    where
+        F: FnMut(&I::Item, &I::Item) -> bool,
+    {
+        self.keep(selector)
+    }
+
+    /// yields all elements of every run instead of the kept one
+    pub fn elements(self) -> RunElements<I> {
+        RunElements {
+            iterator: self.iterator,
+            front: self.front,
+            position: self.position,
+        }
+    }
+}
+
+impl<I, P> Iterator for Runs<I, P>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+    P: Keep<I::Item>,
+{
+    type Item = Span<I::Item>;
+
+    fn next(&mut self) -> Option<Span<I::Item>> {
+        let first = self.front.take().or_else(|| self.iterator.next())?;
+        let mut run = Run::new(first);
+        let mut len = 1;
+        for next in self.iterator.by_ref() {
+            if run.reference(false) != &next {
+                self.front = Some(next);
+                break;
+            }
+            run.push(&mut self.keep, next, false);
+            len += 1;
+        }
+        let start = self.position;
+        self.position += len;
+        Some(Span::new(run.kept, start, len))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        size_hint(self.iterator.size_hint(), usize::from(self.front.is_some()))
+    }
+}
+
+impl<I, P> FusedIterator for Runs<I, P>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+    P: Keep<I::Item>,
+{
+}
+
+/// groups consecutive equal elements into runs, yielding all elements of every run with its span
 #[derive(Debug, Clone)]
-pub struct Dedup<I>
+pub struct RunElements<I>
 where
     I: Iterator,
 {
-    iterator: I,
-    current: Option<I::Item>,
+    iterator: Fuse<I>,
+    front: Option<I::Item>,
+    position: usize,
 }
 
-impl<I> Iterator for Dedup<I>
+impl<I> Iterator for RunElements<I>
 where
     I: Iterator,
     I::Item: PartialEq,
 {
-    type Item = I::Item;
+    type Item = Span<Vec<I::Item>>;
 
-    fn next(&mut self) -> Option<I::Item> {
-        let current = self.current.take()?;
-        let self_current = &mut self.current;
-        Some(
-            self.iterator
-                .try_fold(current, |acc, next| match acc == next {
-                    true => Ok(next),
-                    false => {
-                        *self_current = Some(next);
-                        Err(acc)
-                    }
-                })
-                .unwrap_or_else(|x| x),
-        )
+    fn next(&mut self) -> Option<Span<Vec<I::Item>>> {
+        let first = self.front.take().or_else(|| self.iterator.next())?;
+        let mut run = vec![first];
+        for next in self.iterator.by_ref() {
+            if run.last() != Some(&next) {
+                self.front = Some(next);
+                break;
+            }
+            run.push(next);
+        }
+        let (start, len) = (self.position, run.len());
+        self.position += len;
+        Some(Span::new(run, start, len))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        size_hint(self.iterator.size_hint(), usize::from(self.front.is_some()))
     }
 }
 
-/// provides the `dedup` method on `Iterator`s
-pub trait DedupAdapter: Iterator {
-    fn dedup(mut self) -> Dedup<Self>
+impl<I> FusedIterator for RunElements<I>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+{
+}
+
+/// provides the `runs` method on `Iterator`s
+pub trait RunsAdapter: Iterator {
+    fn runs(self) -> Runs<Self>
     where
         Self: Sized,
     {
-        Dedup {
-            current: self.next(),
-            iterator: self,
+        Runs {
+            iterator: self.fuse(),
+            front: None,
+            position: 0,
+            keep: Last,
         }
     }
 }
 
-impl<I> DedupAdapter for I where I: Iterator {}
+impl<I> RunsAdapter for I where I: Iterator {}
 
-/* # dedup by */
+/* # runs by key */
 
-/// removes consecutive elements, whose equality is asserted by provided function
+/// groups consecutive elements, which give equal outputs from provided function, into runs,
+/// yielding the kept element of every run with its span
+///
+/// the output is computed once per element and kept alongside it for the rest of the run
 #[derive(Debug, Clone)]
-pub struct DedupBy<I, F>
+pub struct RunsByKey<I, F, K, P = Last>
 where
     I: Iterator,
+    F: Fn(&I::Item) -> K,
 {
-    iterator: I,
-    current: Option<I::Item>,
-    equivalence: F,
+    iterator: Fuse<I>,
+    front: Option<(I::Item, K)>,
+    position: usize,
+    function: F,
+    keep: P,
 }
 
-impl<I, F> Iterator for DedupBy<I, F>
+impl<I, F, K, P> RunsByKey<I, F, K, P>
 where
     I: Iterator,
-    F: Fn(&I::Item, &I::Item) -> bool,
+    F: Fn(&I::Item) -> K,
 {
-    type Item = I::Item;
-
-    fn next(&mut self) -> Option<I::Item> {
-        let current = self.current.take()?;
-        let self_current = &mut self.current;
-        Some(
-            self.iterator
-                .try_fold(current, |acc, next| match (self.equivalence)(&acc, &next) {
-                    true => Ok(next),
-                    false => {
-                        *self_current = Some(next);
-                        Err(acc)
-                    }
-                })
-                .unwrap_or_else(|x| x),
-        )
+    /// chooses which element of every run is yielded
+    pub fn keep<Q>(self, keep: Q) -> RunsByKey<I, F, K, Q>
+    where
+        Q: Keep<I::Item>,
+    {
+        RunsByKey {
+            iterator: self.iterator,
+            front: self.front,
+            position: self.position,
+            function: self.function,
+            keep,
+        }
     }
-}
 
-/// provides the `dedup_by` method on `Iterator`s
-pub trait DedupByAdapter<F>: Iterator {
-    fn dedup_by(mut self, equivalence: F) -> DedupBy<Self, F>
+    /// keeps the later element of a run, whenever provided function returns `true` for the kept and the later element
+    pub fn keep_by<G>(self, selector: G) -> RunsByKey<I, F, K, G>
     where
-        Self: Sized,
-        F: Fn(&Self::Item, &Self::Item) -> bool,
+        G: FnMut(&I::Item, &I::Item) -> bool,
     {
-        DedupBy {
-            current: self.next(),
-            iterator: self,
-            equivalence,
+        self.keep(selector)
+    }
+
+    /// yields all elements of every run instead of the kept one
+    pub fn elements(self) -> RunElementsByKey<I, F, K> {
+        RunElementsByKey {
+            iterator: self.iterator,
+            front: self.front,
+            position: self.position,
+            function: self.function,
         }
     }
 }
 
-impl<I, F> DedupByAdapter<F> for I where I: Iterator {}
+impl<I, F, K, P> Iterator for RunsByKey<I, F, K, P>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+    K: PartialEq,
+    P: Keep<I::Item>,
+{
+    type Item = Span<I::Item>;
 
-/* # dedup by key */
+    fn next(&mut self) -> Option<Span<I::Item>> {
+        let function = &self.function;
+        let keyed = |item| {
+            let key = function(&item);
+            (item, key)
+        };
+        let (mut kept, key) = self
+            .front
+            .take()
+            .or_else(|| self.iterator.next().map(keyed))?;
+        let mut len = 1;
+        for (next, next_key) in self.iterator.by_ref().map(keyed) {
+            if next_key != key {
+                self.front = Some((next, next_key));
+                break;
+            }
+            if self.keep.replace(&kept, &next) {
+                kept = next;
+            }
+            len += 1;
+        }
+        let start = self.position;
+        self.position += len;
+        Some(Span::new(kept, start, len))
+    }
 
-/// removes consecutive elements, which give equal outputs from provided function
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        size_hint(self.iterator.size_hint(), usize::from(self.front.is_some()))
+    }
+}
+
+impl<I, F, K, P> FusedIterator for RunsByKey<I, F, K, P>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+    K: PartialEq,
+    P: Keep<I::Item>,
+{
+}
+
+/// groups consecutive elements, which give equal outputs from provided function, into runs,
+/// yielding all elements of every run with its span
 #[derive(Debug, Clone)]
-pub struct DedupByKey<I, F, K>
+pub struct RunElementsByKey<I, F, K>
 where
     I: Iterator,
     F: Fn(&I::Item) -> K,
 {
-    iterator: I,
-    current: Option<I::Item>,
+    iterator: Fuse<I>,
+    front: Option<(I::Item, K)>,
+    position: usize,
     function: F,
 }
 
-impl<I, F, K> Iterator for DedupByKey<I, F, K>
+impl<I, F, K> Iterator for RunElementsByKey<I, F, K>
 where
     I: Iterator,
     F: Fn(&I::Item) -> K,
     K: PartialEq,
 {
-    type Item = I::Item;
+    type Item = Span<Vec<I::Item>>;
 
-    fn next(&mut self) -> Option<I::Item> {
-        let current = self.current.take()?;
-        let self_current = &mut self.current;
-        Some(
-            self.iterator
-                .try_fold(current, |acc, next| {
-                    match (self.function)(&acc) == (self.function)(&next) {
-                        true => Ok(next),
-                        false => {
-                            *self_current = Some(next);
-                            Err(acc)
-                        }
-                    }
-                })
-                .unwrap_or_else(|x| x),
-        )
+    fn next(&mut self) -> Option<Span<Vec<I::Item>>> {
+        let function = &self.function;
+        let keyed = |item| {
+            let key = function(&item);
+            (item, key)
+        };
+        let (first, key) = self
+            .front
+            .take()
+            .or_else(|| self.iterator.next().map(keyed))?;
+        let mut run = vec![first];
+        for (next, next_key) in self.iterator.by_ref().map(keyed) {
+            if next_key != key {
+                self.front = Some((next, next_key));
+                break;
+            }
+            run.push(next);
+        }
+        let (start, len) = (self.position, run.len());
+        self.position += len;
+        Some(Span::new(run, start, len))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        size_hint(self.iterator.size_hint(), usize::from(self.front.is_some()))
     }
 }
 
-/// Provides the `dedup_by_key` method on `Iterator`s.
-pub trait DedupByKeyAdapter<F, K>: Iterator {
-    fn dedup_by_key(mut self, function: F) -> DedupByKey<Self, F, K>
+impl<I, F, K> FusedIterator for RunElementsByKey<I, F, K>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+    K: PartialEq,
+{
+}
+
+/// provides the `runs_by_key` method on `Iterator`s
+pub trait RunsByKeyAdapter<F, K>: Iterator {
+    fn runs_by_key(self, function: F) -> RunsByKey<Self, F, K>
     where
         Self: Sized,
         F: Fn(&Self::Item) -> K,
     {
-        DedupByKey {
-            current: self.next(),
-            iterator: self,
+        RunsByKey {
+            iterator: self.fuse(),
+            front: None,
+            position: 0,
             function,
+            keep: Last,
         }
     }
 }
 
-impl<I, F, K> DedupByKeyAdapter<F, K> for I where I: Iterator {}
+impl<I, F, K> RunsByKeyAdapter<F, K> for I where I: Iterator {}
 
 #[cfg(test)]
 mod tests {
@@ -247,4 +1555,363 @@ mod tests {
         let v = t.chars().dedup_by_key(|_| 0_u8).collect::<String>();
         assert_eq!(&v, "z");
     }
+
+    #[test]
+    fn dedup_by_key_keep_first() {
+        let og = [(0, 'a'), (0, 'b'), (1, 'c'), (1, 'd'), (0, 'e')];
+        let dp = og
+            .into_iter()
+            .dedup_by_key(|pair| pair.0)
+            .keep(First)
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [(0, 'a'), (1, 'c'), (0, 'e')]);
+    }
+
+    #[test]
+    fn dedup_keep_first_and_last_of_equal_elements() {
+        let og: [i32; 7] = [10, 20, 20, 21, 30, 30, 20];
+        let first = og.iter().dedup().keep(First).collect::<Vec<_>>();
+        let last = og.iter().dedup().collect::<Vec<_>>();
+        assert!(std::ptr::eq(first[1], &og[1]));
+        assert!(std::ptr::eq(last[1], &og[2]));
+    }
+
+    #[test]
+    fn dedup_by_keep_min_and_max() {
+        let og = [3, 1, 2, 10, 12, 11, 5];
+        let min = og
+            .into_iter()
+            .dedup_by(|a, b| a / 10 == b / 10)
+            .keep(MinByKey(|n: &i32| *n))
+            .collect::<Vec<_>>();
+        let max = og
+            .into_iter()
+            .dedup_by(|a, b| a / 10 == b / 10)
+            .keep(MaxByKey(|n: &i32| *n))
+            .collect::<Vec<_>>();
+        assert_eq!(min, [1, 10, 5]);
+        assert_eq!(max, [3, 12, 5]);
+    }
+
+    #[test]
+    fn dedup_by_key_keep_min_ties_keep_first() {
+        let og = [(0, 2, 'a'), (0, 1, 'b'), (0, 1, 'c'), (1, 0, 'd')];
+        let dp = og
+            .into_iter()
+            .dedup_by_key(|triple| triple.0)
+            .keep(MinByKey(|triple: &(u8, u8, char)| triple.1))
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [(0, 1, 'b'), (1, 0, 'd')]);
+    }
+
+    #[test]
+    fn dedup_by_keep_custom_selector() {
+        let og = "ttu    teżż  czasem   jakkaś litterka     dwa  rrazy";
+        let dp = og
+            .chars()
+            .dedup_by_key(|c| c.is_whitespace())
+            .keep_by(|kept, candidate| candidate.is_alphabetic() && kept < candidate)
+            .collect::<String>();
+        assert_eq!(&dp, "u ż z ś t w z");
+    }
+
+    #[test]
+    fn dedup_by_compares_with_previous_element() {
+        let og = [1.0, 1.4, 1.8, 2.2, 5.0, 5.3];
+        let dp = og
+            .into_iter()
+            .dedup_by(|a: &f64, b: &f64| (a - b).abs() < 0.5)
+            .keep(First)
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [1.0, 5.0]);
+    }
+
+    #[test]
+    fn dedup_by_anchored_bounds_drift() {
+        let og = [1.0, 1.4, 1.8, 2.2, 5.0, 5.3];
+        let dp = og
+            .into_iter()
+            .dedup_by(|a: &f64, b: &f64| (a - b).abs() < 0.5)
+            .anchored()
+            .keep(First)
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [1.0, 1.8, 5.0]);
+    }
+
+    #[test]
+    fn dedup_by_anchored_keep_last() {
+        let og = [10, 12, 14, 16, 18, 20];
+        let dp = og
+            .into_iter()
+            .dedup_by(|a: &i32, b: &i32| b - a < 5)
+            .anchored()
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [14, 20]);
+    }
+
+    #[test]
+    fn construction_does_not_pull_from_source() {
+        let pulled = std::cell::Cell::new(0);
+        let mut dp = [1, 1, 2]
+            .into_iter()
+            .inspect(|_| pulled.set(pulled.get() + 1))
+            .dedup();
+        assert_eq!(pulled.get(), 0);
+        assert_eq!(dp.next(), Some(1));
+        assert_eq!(pulled.get(), 3);
+    }
+
+    #[test]
+    fn size_hint_bounds_remaining_runs() {
+        let og: [i32; 7] = [10, 20, 20, 21, 30, 30, 20];
+        let mut dp = og.into_iter().dedup();
+        assert_eq!(dp.size_hint(), (1, Some(7)));
+        dp.next();
+        assert_eq!(dp.size_hint(), (1, Some(6)));
+        assert_eq!(dp.by_ref().count(), 4);
+        assert_eq!(dp.size_hint(), (0, Some(0)));
+        assert_eq!(Vec::<u8>::new().iter().dedup().size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn stays_exhausted_over_unfused_source() {
+        let mut flip = false;
+        let source = std::iter::from_fn(|| {
+            flip = !flip;
+            flip.then_some(0)
+        });
+        let mut dp = source.dedup();
+        assert_eq!(dp.next(), Some(0));
+        assert_eq!(dp.next(), None);
+        assert_eq!(dp.next(), None);
+    }
+
+    #[test]
+    fn reversed_yields_same_elements() {
+        let og = "ttu    teżż  czasem   jakkaś litterka     dwa  rrazy";
+        let dp = og.chars().dedup().rev().collect::<String>();
+        assert_eq!(
+            dp,
+            og.chars()
+                .dedup()
+                .collect::<Vec<_>>()
+                .into_iter()
+                .rev()
+                .collect::<String>()
+        );
+        let og = [(0, 'a'), (0, 'b'), (1, 'c'), (1, 'd'), (0, 'e')];
+        let dp = og
+            .into_iter()
+            .dedup_by_key(|pair| pair.0)
+            .keep(First)
+            .rev()
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [(0, 'e'), (1, 'c'), (0, 'a')]);
+    }
+
+    #[test]
+    fn custom_selector_need_not_be_an_order() {
+        let og = [1, 2, 3, 4, 5, 6];
+        let dp = og
+            .into_iter()
+            .dedup_by(|_, _| true)
+            .keep_by(|kept, candidate| (kept + candidate) % 3 == 0)
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [5]);
+        let dp = og
+            .into_iter()
+            .dedup_by(|_, _| true)
+            .keep(MaxByKey(|n: &i32| n % 3))
+            .rev()
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [2]);
+    }
+
+    #[test]
+    fn both_ends_meet_inside_a_run() {
+        let og = [3, 1, 2, 10, 12, 11, 5, 5, 14, 13];
+        let forward = og
+            .into_iter()
+            .dedup_by(|a, b| a / 10 == b / 10)
+            .keep(MinByKey(|n: &i32| *n))
+            .collect::<Vec<_>>();
+        (0..1 << og.len()).for_each(|pattern: u32| {
+            let mut dp = og
+                .into_iter()
+                .dedup_by(|a, b| a / 10 == b / 10)
+                .keep(MinByKey(|n: &i32| *n));
+            let mut front = Vec::new();
+            let mut back = Vec::new();
+            for bit in 0..og.len() {
+                match pattern >> bit & 1 {
+                    0 => front.extend(dp.next()),
+                    _ => back.extend(dp.next_back()),
+                }
+            }
+            front.extend(back.into_iter().rev());
+            assert_eq!(front, forward);
+        });
+    }
+
+    #[test]
+    fn dedup_by_key_computes_every_key_once() {
+        let calls = std::cell::Cell::new(0);
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og
+            .chars()
+            .dedup_by_key(|c| {
+                calls.set(calls.get() + 1);
+                c.to_ascii_uppercase()
+            })
+            .collect::<String>();
+        assert_eq!(&dp, "sporo podwojonych liter");
+        assert_eq!(calls.get(), og.chars().count());
+    }
+
+    #[test]
+    fn dedup_by_key_ref_borrows_from_element() {
+        #[derive(Debug, PartialEq)]
+        struct Test {
+            name: String,
+            id: u8,
+        }
+        let og = ["raz", "raz", "dwa", "raz"]
+            .into_iter()
+            .enumerate()
+            .map(|(id, name)| Test {
+                name: name.to_string(),
+                id: id as u8,
+            })
+            .collect::<Vec<_>>();
+        let dp = og
+            .into_iter()
+            .dedup_by_key_ref(|test| test.name.as_str())
+            .map(|test| test.id)
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [1, 2, 3]);
+    }
+
+    #[test]
+    fn try_dedup_passes_errors_through() {
+        let og = ["raz", "raz", "dwa", "x", "dwa", "dwa", "trzy"].map(|word| {
+            if word == "x" {
+                Err(word.len())
+            } else {
+                Ok(word)
+            }
+        });
+        let dp = og.into_iter().try_dedup().collect::<Vec<_>>();
+        assert_eq!(dp, [Ok("raz"), Ok("dwa"), Err(1), Ok("dwa"), Ok("trzy")]);
+        let dp = og
+            .into_iter()
+            .try_dedup()
+            .short_circuit()
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [Ok("raz"), Ok("dwa"), Err(1)]);
+    }
+
+    #[test]
+    fn try_dedup_by_key_passes_key_errors_through() {
+        let og = ["1", "01", "2", "dwa", "2", "02", "3"];
+        let mut dp = og
+            .into_iter()
+            .map(Ok)
+            .try_dedup_by_key(|word| word.parse::<u8>().map_err(|_| *word));
+        assert_eq!(dp.next(), Some(Ok("01")));
+        assert_eq!(dp.next(), Some(Ok("2")));
+        assert_eq!(dp.next(), Some(Err("dwa")));
+        assert_eq!(dp.collect::<Vec<_>>(), [Ok("02"), Ok("3")]);
+    }
+
+    #[test]
+    fn try_dedup_by_key_short_circuits() {
+        let og = [Ok(1), Ok(1), Err("jeden"), Ok(2), Err("dwa")];
+        let mut dp = og
+            .into_iter()
+            .try_dedup_by_key(|&n| Ok(n / 2))
+            .short_circuit();
+        assert_eq!(dp.next(), Some(Ok(1)));
+        assert_eq!(dp.next(), Some(Err("jeden")));
+        assert_eq!(dp.size_hint(), (0, Some(0)));
+        assert_eq!(dp.next(), None);
+    }
+
+    #[test]
+    fn unique_only_drops_runs() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og.chars().unique_only().collect::<String>();
+        assert_eq!(&dp, "poo podwooch lie");
+        let mut dp = [1, 1, 2, 3, 3].into_iter().unique_only();
+        assert_eq!(dp.next(), Some(2));
+        assert_eq!(dp.next(), None);
+        assert_eq!(dp.next(), None);
+    }
+
+    #[test]
+    fn unique_only_by_key_drops_runs() {
+        let og = ["Raz", "RAZ", "dwa", "Trzy", "trzy", "TRZY", "raz"];
+        let dp = og
+            .into_iter()
+            .unique_only_by_key(|word| word.to_lowercase())
+            .collect::<Vec<_>>();
+        assert_eq!(dp, ["dwa", "raz"]);
+    }
+
+    #[test]
+    fn runs_report_spans() {
+        let og = "ssporrro";
+        let dp = og
+            .chars()
+            .runs()
+            .map(|span| (span.item, span.len, span.start, span.end))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dp,
+            [
+                ('s', 2, 0, 1),
+                ('p', 1, 2, 2),
+                ('o', 1, 3, 3),
+                ('r', 3, 4, 6),
+                ('o', 1, 7, 7)
+            ]
+        );
+        assert_eq!(og.chars().runs().count(), og.chars().dedup().count());
+    }
+
+    #[test]
+    fn runs_keep_and_collect_elements() {
+        let og = [(1, 'a'), (1, 'b'), (2, 'c'), (2, 'd'), (2, 'e')];
+        let dp = og
+            .into_iter()
+            .runs_by_key(|pair| pair.0)
+            .keep(First)
+            .map(|span| span.item.1)
+            .collect::<String>();
+        assert_eq!(&dp, "ac");
+        let dp = og
+            .into_iter()
+            .runs_by_key(|pair| pair.0)
+            .elements()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dp[1],
+            Span {
+                item: vec![(2, 'c'), (2, 'd'), (2, 'e')],
+                len: 3,
+                start: 2,
+                end: 4
+            }
+        );
+    }
+
+    #[test]
+    fn run_elements_compress_sensor_log() {
+        let og = [20.5, 20.5, 20.5, 21.0, 20.5, 20.5];
+        let mut dp = og.into_iter().runs().elements();
+        let first = dp.next().unwrap();
+        assert_eq!((first.item.len(), first.start, first.end), (3, 0, 2));
+        assert_eq!(dp.size_hint(), (1, Some(3)));
+        let rest = dp.map(|span| (span.item[0], span.len)).collect::<Vec<_>>();
+        assert_eq!(rest, [(21.0, 1), (20.5, 2)]);
+    }
 }
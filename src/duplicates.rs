@@ -0,0 +1,363 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+#[cfg(feature = "std")]
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, Hash},
+};
+
+/* # occurrence store */
+
+/// where an element first occurred, and how many times it has occurred so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Occurrence {
+    /// the index of the first occurrence in the input
+    pub first: usize,
+    /// the number of occurrences so far, including the first one
+    pub count: usize,
+}
+
+/// remembers the occurrences of every element
+pub trait OccurrenceStore<K> {
+    /// records an occurrence of `key` at `index`, giving back all occurrences recorded so far
+    fn record(&mut self, key: K, index: usize) -> Occurrence;
+}
+
+fn first(index: usize) -> Occurrence {
+    Occurrence {
+        first: index,
+        count: 0,
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, S> OccurrenceStore<K> for HashMap<K, Occurrence, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn record(&mut self, key: K, index: usize) -> Occurrence {
+        let occurrence = self.entry(key).or_insert_with(|| first(index));
+        occurrence.count += 1;
+        *occurrence
+    }
+}
+
+impl<K> OccurrenceStore<K> for BTreeMap<K, Occurrence>
+where
+    K: Ord,
+{
+    fn record(&mut self, key: K, index: usize) -> Occurrence {
+        let occurrence = self.entry(key).or_insert_with(|| first(index));
+        occurrence.count += 1;
+        *occurrence
+    }
+}
+
+impl<K> OccurrenceStore<K> for Vec<(K, Occurrence)>
+where
+    K: PartialEq,
+{
+    fn record(&mut self, key: K, index: usize) -> Occurrence {
+        let position = match self.iter().position(|(old, _)| old == &key) {
+            Some(position) => position,
+            None => {
+                self.push((key, first(index)));
+                self.len() - 1
+            }
+        };
+        let occurrence = &mut self[position].1;
+        occurrence.count += 1;
+        *occurrence
+    }
+}
+
+/* # duplicates */
+
+/// a repeated element, together with its position and the position of its first occurrence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Duplicate<T> {
+    /// the repeated element itself
+    pub item: T,
+    /// the index of the repeated element in the input
+    pub index: usize,
+    /// the index of the first occurrence of an equal element in the input
+    pub first: usize,
+}
+
+/// yields elements, which give an output from provided function equal to that of an earlier element,
+/// i.e. the elements the matching dedup adapter would drop
+#[derive(Debug, Clone)]
+pub struct DuplicatesByKey<I, S, F, K>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+{
+    iterator: I,
+    seen: S,
+    function: F,
+    position: usize,
+    once: bool,
+}
+
+impl<I, S, F, K> DuplicatesByKey<I, S, F, K>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+{
+    /// yields only the first repetition of every output
+    pub fn once(self) -> Self {
+        Self { once: true, ..self }
+    }
+
+    /// the occurrences of the outputs seen so far
+    pub fn seen(&self) -> &S {
+        &self.seen
+    }
+
+    /// stops iterating, giving back the occurrences of the outputs seen so far
+    pub fn into_seen(self) -> S {
+        self.seen
+    }
+}
+
+impl<I, S, F, K> Iterator for DuplicatesByKey<I, S, F, K>
+where
+    I: Iterator,
+    S: OccurrenceStore<K>,
+    F: Fn(&I::Item) -> K,
+{
+    type Item = Duplicate<I::Item>;
+
+    #[allow(clippy::while_let_on_iterator, reason = "seems more readable here")]
+    fn next(&mut self) -> Option<Duplicate<I::Item>> {
+        while let Some(item) = self.iterator.next() {
+            let index = self.position;
+            self.position += 1;
+            let occurrence = self.seen.record((self.function)(&item), index);
+            if occurrence.count == 2 || (occurrence.count > 2 && !self.once) {
+                return Some(Duplicate {
+                    item,
+                    index,
+                    first: occurrence.first,
+                });
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iterator.size_hint().1)
+    }
+}
+
+/// yields elements equal to an earlier element
+pub type Duplicates<I, S> = DuplicatesByKey<
+    I,
+    S,
+    fn(&<I as Iterator>::Item) -> <I as Iterator>::Item,
+    <I as Iterator>::Item,
+>;
+
+/// yields elements equal to an earlier element, remembering occurrences in a `HashMap`
+#[cfg(feature = "std")]
+pub type DuplicatesHash<I, S = RandomState> =
+    Duplicates<I, HashMap<<I as Iterator>::Item, Occurrence, S>>;
+
+/// yields elements, whose output from provided function was seen before, remembering occurrences in a `HashMap`
+#[cfg(feature = "std")]
+pub type DuplicatesHashByKey<I, F, K, S = RandomState> =
+    DuplicatesByKey<I, HashMap<K, Occurrence, S>, F, K>;
+
+/// yields elements equal to an earlier element, remembering occurrences in a `BTreeMap`
+pub type DuplicatesOrd<I> = Duplicates<I, BTreeMap<<I as Iterator>::Item, Occurrence>>;
+
+/// yields elements, whose output from provided function was seen before, remembering occurrences in a `BTreeMap`
+pub type DuplicatesOrdByKey<I, F, K> = DuplicatesByKey<I, BTreeMap<K, Occurrence>, F, K>;
+
+/// yields elements equal to an earlier element, remembering occurrences in a `Vec`
+pub type DuplicatesNonCon<I> = Duplicates<I, Vec<(<I as Iterator>::Item, Occurrence)>>;
+
+/// yields elements, whose output from provided function was seen before, remembering occurrences in a `Vec`
+pub type DuplicatesNonConByKey<I, F, K> = DuplicatesByKey<I, Vec<(K, Occurrence)>, F, K>;
+
+/// provides the `duplicates` methods on `Iterator`s
+pub trait DuplicatesAdapter: Iterator {
+    fn duplicates_store_by_key<S, F, K>(
+        self,
+        seen: S,
+        function: F,
+    ) -> DuplicatesByKey<Self, S, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        DuplicatesByKey {
+            iterator: self,
+            seen,
+            function,
+            position: 0,
+            once: false,
+        }
+    }
+
+    fn duplicates_store<S>(self, seen: S) -> Duplicates<Self, S>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        self.duplicates_store_by_key(seen, Self::Item::clone)
+    }
+
+    #[cfg(feature = "std")]
+    fn duplicates_hash(self) -> DuplicatesHash<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        self.duplicates_store(HashMap::new())
+    }
+
+    #[cfg(feature = "std")]
+    fn duplicates_hash_by_key<F, K>(self, function: F) -> DuplicatesHashByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.duplicates_store_by_key(HashMap::new(), function)
+    }
+
+    fn duplicates_ord(self) -> DuplicatesOrd<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        self.duplicates_store(BTreeMap::new())
+    }
+
+    fn duplicates_ord_by_key<F, K>(self, function: F) -> DuplicatesOrdByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.duplicates_store_by_key(BTreeMap::new(), function)
+    }
+
+    fn duplicates_non_con(self) -> DuplicatesNonCon<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        self.duplicates_store(Vec::new())
+    }
+
+    fn duplicates_non_con_by_key<F, K>(self, function: F) -> DuplicatesNonConByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.duplicates_store_by_key(Vec::new(), function)
+    }
+}
+
+impl<I> DuplicatesAdapter for I where I: Iterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::hashable::DedupHashAdapter;
+    use crate::ordable::DedupOrdAdapter;
+
+    #[test]
+    fn empty_iterator_has_no_duplicates() {
+        let og = Vec::<u8>::new();
+        assert_eq!(og.iter().duplicates_ord().count(), 0);
+        assert_eq!(og.iter().duplicates_non_con().count(), 0);
+    }
+
+    #[test]
+    fn duplicates_complement_dedup() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og.chars().dedup_ord().collect::<String>();
+        let repeated = og
+            .chars()
+            .duplicates_ord()
+            .map(|duplicate| duplicate.item)
+            .collect::<String>();
+        assert_eq!(dp.len() + repeated.len(), og.len());
+        assert_eq!(&repeated, "srropoojony trr");
+        let vec = og
+            .chars()
+            .duplicates_non_con()
+            .map(|duplicate| duplicate.item);
+        assert_eq!(vec.collect::<String>(), repeated);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_duplicates_complement_dedup_hash() {
+        assert_eq!(Vec::<u8>::new().iter().duplicates_hash().count(), 0);
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og.chars().dedup_hash().collect::<String>();
+        let repeated = og
+            .chars()
+            .duplicates_hash()
+            .map(|duplicate| duplicate.item)
+            .collect::<String>();
+        assert_eq!(dp.len() + repeated.len(), og.len());
+        assert_eq!(&repeated, "srropoojony trr");
+    }
+
+    #[test]
+    fn duplicate_points_at_first_occurrence() {
+        let og: [i32; 7] = [10, 20, 20, 21, 30, 30, 20];
+        let dp = og.into_iter().duplicates_ord().collect::<Vec<_>>();
+        assert_eq!(
+            dp,
+            [
+                Duplicate {
+                    item: 20,
+                    index: 2,
+                    first: 1
+                },
+                Duplicate {
+                    item: 30,
+                    index: 5,
+                    first: 4
+                },
+                Duplicate {
+                    item: 20,
+                    index: 6,
+                    first: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn once_yields_every_key_once() {
+        let og = ["Raz", "dwa", "RAZ", "Trzy", "DWA", "trzy", "raz"];
+        let mut dp = og
+            .into_iter()
+            .duplicates_hash_by_key(|word| word.to_lowercase())
+            .once();
+        let reported = dp
+            .by_ref()
+            .map(|duplicate| (duplicate.item, duplicate.first))
+            .collect::<Vec<_>>();
+        assert_eq!(reported, [("RAZ", 0), ("DWA", 1), ("trzy", 3)]);
+        assert_eq!(dp.seen()["raz"].count, 3);
+    }
+
+    #[test]
+    fn non_con_by_key_without_hash_or_ord() {
+        let og = [0.5, 1.5, 0.25, 2.5, 1.75];
+        let dp = og
+            .into_iter()
+            .duplicates_non_con_by_key(|x: &f64| x.floor())
+            .map(|duplicate| (duplicate.index, duplicate.first))
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [(2, 0), (4, 1)]);
+    }
+}
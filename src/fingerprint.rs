@@ -0,0 +1,317 @@
+use std::{
+    collections::{hash_map::RandomState, HashSet},
+    hash::{BuildHasher, Hash, Hasher},
+};
+
+use crate::hasher;
+
+/* # fingerprint */
+
+/// a fixed-width digest, remembered in place of an element
+///
+/// two distinct elements may share a digest, in which case the later one is dropped as a duplicate;
+/// among `n` distinct elements this happens with probability of about `n² / 2^(b + 1)` for `b` bits,
+/// e.g. about 3% for a billion elements with `u64`, and below `10^-20` with `u128`
+pub trait Fingerprint: Copy + Eq + Hash {
+    /// digests `item` with hashers built by `hash_builder`
+    fn digest<T, S>(item: &T, hash_builder: &S) -> Self
+    where
+        T: Hash + ?Sized,
+        S: BuildHasher;
+}
+
+macro_rules! truncated {
+    ($($width:ty),*) => {$(
+        /// the low bits of the hash, for stores packing many fingerprints, such as a cuckoo filter
+        impl Fingerprint for $width {
+            fn digest<T, S>(item: &T, hash_builder: &S) -> Self
+            where
+                T: Hash + ?Sized,
+                S: BuildHasher,
+            {
+                hash_builder.hash_one(item) as $width
+            }
+        }
+    )*};
+}
+
+truncated!(u8, u16, u32);
+
+impl Fingerprint for u64 {
+    fn digest<T, S>(item: &T, hash_builder: &S) -> Self
+    where
+        T: Hash + ?Sized,
+        S: BuildHasher,
+    {
+        hash_builder.hash_one(item)
+    }
+}
+
+/// salts the words mixed into the second half of a wide digest
+const SALT: u64 = 0x243f_6a88_85a3_08d3;
+
+/// passes every word written through `splitmix` before the inner hasher sees it
+///
+/// this makes the second half of a wide digest independent of the first, even under a hasher,
+/// which combines words linearly, such as `FastHasher`
+struct Mixed<H>(H);
+
+impl<H> Hasher for Mixed<H>
+where
+    H: Hasher,
+{
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in chunks.by_ref() {
+            let word = u64::from_le_bytes(chunk.try_into().expect("chunk of eight bytes"));
+            self.0.write_u64(hasher::splitmix(word ^ SALT));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            let word = u64::from_le_bytes(word) ^ ((rest.len() as u64) << 56);
+            self.0.write_u64(hasher::splitmix(word ^ SALT));
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+}
+
+impl Fingerprint for u128 {
+    fn digest<T, S>(item: &T, hash_builder: &S) -> Self
+    where
+        T: Hash + ?Sized,
+        S: BuildHasher,
+    {
+        let mut mixed = Mixed(hash_builder.build_hasher());
+        item.hash(&mut mixed);
+        (u128::from(hash_builder.hash_one(item)) << 64) | u128::from(mixed.finish())
+    }
+}
+
+/* # verify */
+
+/// confirms, that an element whose fingerprint was already seen is really a duplicate
+pub trait Verify<T> {
+    /// checks whether `item` equals an element yielded before
+    fn is_duplicate(&mut self, item: &T) -> bool;
+}
+
+/// trusts fingerprints, treating every element with a seen fingerprint as a duplicate
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Trust;
+
+impl<T> Verify<T> for Trust {
+    fn is_duplicate(&mut self, _item: &T) -> bool {
+        true
+    }
+}
+
+/// an exact check, e.g. a lookup in an external store, consulted only when fingerprints match
+impl<T, F> Verify<T> for F
+where
+    F: FnMut(&T) -> bool,
+{
+    fn is_duplicate(&mut self, item: &T) -> bool {
+        self(item)
+    }
+}
+
+/* # dedup fingerprint */
+
+/// removes equal elements, remembering only their fingerprints in a `HashSet`
+///
+/// elements are neither cloned nor kept, so memory use depends only on the width of the fingerprint
+#[derive(Debug, Clone)]
+pub struct DedupFingerprint<I, D, S = RandomState, V = Trust> {
+    iterator: I,
+    seen: HashSet<D>,
+    hash_builder: S,
+    verify: V,
+}
+
+impl<I, D, S, V> DedupFingerprint<I, D, S, V> {
+    /// consults provided check whenever a fingerprint was already seen, yielding the element if the check fails
+    pub fn verify_with<W>(self, verify: W) -> DedupFingerprint<I, D, S, W>
+    where
+        I: Iterator,
+        W: FnMut(&I::Item) -> bool,
+    {
+        DedupFingerprint {
+            iterator: self.iterator,
+            seen: self.seen,
+            hash_builder: self.hash_builder,
+            verify,
+        }
+    }
+
+    /// the fingerprints seen so far
+    pub fn seen(&self) -> &HashSet<D> {
+        &self.seen
+    }
+
+    /// stops iterating, giving back the fingerprints seen so far
+    pub fn into_seen(self) -> HashSet<D> {
+        self.seen
+    }
+}
+
+impl<I, D, S, V> Iterator for DedupFingerprint<I, D, S, V>
+where
+    I: Iterator,
+    I::Item: Hash,
+    D: Fingerprint,
+    S: BuildHasher,
+    V: Verify<I::Item>,
+{
+    type Item = I::Item;
+
+    #[allow(clippy::while_let_on_iterator, reason = "seems more readable here")]
+    fn next(&mut self) -> Option<I::Item> {
+        while let Some(item) = self.iterator.next() {
+            if self.seen.insert(D::digest(&item, &self.hash_builder))
+                || !self.verify.is_duplicate(&item)
+            {
+                return Some(item);
+            }
+        }
+        None
+    }
+}
+
+/// provides the `dedup_fingerprint` method on `Iterator`s
+pub trait DedupFingerprintAdapter: Iterator {
+    fn dedup_fingerprint<D>(self) -> DedupFingerprint<Self, D>
+    where
+        Self: Sized,
+        D: Fingerprint,
+    {
+        self.dedup_fingerprint_with_hasher(RandomState::new())
+    }
+
+    /// as `dedup_fingerprint`, but digests the elements with provided `BuildHasher`,
+    /// which keeps fingerprints comparable across runs if the hasher is deterministic
+    fn dedup_fingerprint_with_hasher<D, S>(self, hash_builder: S) -> DedupFingerprint<Self, D, S>
+    where
+        Self: Sized,
+        D: Fingerprint,
+        S: BuildHasher,
+    {
+        DedupFingerprint {
+            iterator: self,
+            seen: HashSet::new(),
+            hash_builder,
+            verify: Trust,
+        }
+    }
+}
+
+impl<I> DedupFingerprintAdapter for I where I: Iterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hasher::FastState;
+
+    #[test]
+    fn deduplicate_empty_iterator() {
+        let og = Vec::<u8>::new();
+        let dp = og.iter().dedup_fingerprint::<u64>().count();
+        assert_eq!(dp, 0);
+    }
+
+    #[test]
+    fn remove_duplicate_character() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og.chars().dedup_fingerprint::<u64>().collect::<String>();
+        assert_eq!(&dp, "spor dwjnychlite");
+        let dp = og.chars().dedup_fingerprint::<u128>().collect::<String>();
+        assert_eq!(&dp, "spor dwjnychlite");
+    }
+
+    #[test]
+    fn deduplicate_without_clone() {
+        #[derive(Debug, PartialEq, Hash)]
+        struct Test {
+            id: u8,
+            other: Vec<u8>,
+        }
+        let og = [
+            Test {
+                id: 0,
+                other: vec![0, 1, 2],
+            },
+            Test {
+                id: 0,
+                other: vec![0, 1, 2],
+            },
+            Test {
+                id: 1,
+                other: vec![0, 1, 2],
+            },
+        ];
+        let mut dp = og.into_iter().dedup_fingerprint::<u128>();
+        assert_eq!(dp.by_ref().map(|test| test.id).collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(dp.seen().len(), 2);
+    }
+
+    #[test]
+    fn deterministic_hasher_gives_same_fingerprints() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let mut one = og
+            .chars()
+            .dedup_fingerprint_with_hasher::<u64, _>(FastState::with_seed(3));
+        let mut two = og
+            .chars()
+            .dedup_fingerprint_with_hasher::<u64, _>(FastState::with_seed(3));
+        one.by_ref().for_each(drop);
+        two.by_ref().for_each(drop);
+        assert_eq!(one.into_seen(), two.into_seen());
+    }
+
+    #[test]
+    fn wide_digest_halves_collide_independently() {
+        let state = FastState::with_seed(42);
+        let one = [5_u64, 7];
+        let two = [6_u64, 7_u64.wrapping_sub(0xf135_7aea_2e62_a9c5)];
+        assert_eq!(state.hash_one(one), state.hash_one(two));
+        assert_ne!(u128::digest(&one, &state), u128::digest(&two, &state));
+    }
+
+    #[test]
+    fn verify_rescues_colliding_fingerprints() {
+        /// a hasher, under which every element has the same fingerprint
+        #[derive(Default)]
+        struct Constant;
+
+        impl Hasher for Constant {
+            fn write(&mut self, _bytes: &[u8]) {}
+
+            fn finish(&self) -> u64 {
+                0
+            }
+        }
+
+        let og: [i32; 7] = [10, 20, 20, 21, 30, 30, 20];
+        let colliding = og
+            .into_iter()
+            .dedup_fingerprint_with_hasher::<u64, _>(
+                std::hash::BuildHasherDefault::<Constant>::default(),
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(colliding, [10]);
+        let exact = std::cell::RefCell::new(Vec::new());
+        let verified = og
+            .into_iter()
+            .dedup_fingerprint_with_hasher::<u64, _>(
+                std::hash::BuildHasherDefault::<Constant>::default(),
+            )
+            .verify_with(|n| exact.borrow().contains(n))
+            .inspect(|&n| exact.borrow_mut().push(n))
+            .collect::<Vec<_>>();
+        assert_eq!(verified, [10, 20, 21, 30]);
+    }
+}
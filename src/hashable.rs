@@ -1,46 +1,60 @@
-use std::{collections::HashSet, hash::Hash};
+use std::{
+    collections::{hash_map::RandomState, HashMap, HashSet},
+    hash::{BuildHasher, Hash, Hasher},
+};
 
-/* # dedup */
+use crate::store::{
+    self, DedupStore, DedupStoreAdapter, DedupStoreBy, DedupStoreByAdapter, DedupStoreByKey,
+    DedupStoreByKeyAdapter, SeenStore, TryDedupStore, TryDedupStoreAdapter, TryDedupStoreByKey,
+    TryDedupStoreByKeyAdapter,
+};
 
-/// removes consecutive equal elements
-#[derive(Debug, Clone)]
-pub struct DedupHash<I>
-where
-    I: Iterator,
-{
-    iterator: I,
-    seen: HashSet<I::Item>,
-}
+/* # deduplicator */
 
-impl<I> Iterator for DedupHash<I>
-where
-    I: Iterator,
-    I::Item: PartialEq + Eq + Hash + Clone,
-{
-    type Item = I::Item;
-
-    #[allow(clippy::while_let_on_iterator, reason = "seems more readable here")]
-    fn next(&mut self) -> Option<I::Item> {
-        while let Some(item) = self.iterator.next() {
-            if !self.seen.contains(&item) {
-                self.seen.insert(item.clone());
-                return Some(item);
-            }
-        }
-        None
-    }
-}
+/// removes equal elements pushed one at a time, remembering them in a `HashSet`
+pub type Deduplicator<T> = store::Deduplicator<T, HashSet<T>>;
+
+/* # dedup */
+
+/// removes equal elements, remembering them in a `HashSet`
+pub type DedupHash<I, S = RandomState> = DedupStore<I, HashSet<<I as Iterator>::Item, S>>;
 
-/// provides the `dedup` method on `Iterator`s
+/// provides the `dedup_hash` method on `Iterator`s
 pub trait DedupHashAdapter: Iterator {
     fn dedup_hash(self) -> DedupHash<Self>
     where
         Self: Sized,
     {
-        DedupHash {
-            seen: HashSet::new(),
-            iterator: self,
-        }
+        self.dedup_store(HashSet::new())
+    }
+
+    /// as `dedup_hash`, but hashes the elements with provided `BuildHasher`
+    fn dedup_hash_with_hasher<S>(self, hash_builder: S) -> DedupHash<Self, S>
+    where
+        Self: Sized,
+    {
+        self.dedup_store(HashSet::with_hasher(hash_builder))
+    }
+
+    /// as `dedup_hash_with_hasher`, but reserves room for `capacity` distinct elements upfront,
+    /// e.g. for the lower bound of `size_hint`
+    fn dedup_hash_with_capacity_and_hasher<S>(
+        self,
+        capacity: usize,
+        hash_builder: S,
+    ) -> DedupHash<Self, S>
+    where
+        Self: Sized,
+    {
+        self.dedup_store(HashSet::with_capacity_and_hasher(capacity, hash_builder))
+    }
+
+    /// as `dedup_hash`, but treats the elements of `seen` as already yielded
+    fn dedup_hash_seeded<S>(self, seen: HashSet<Self::Item, S>) -> DedupHash<Self, S>
+    where
+        Self: Sized,
+    {
+        self.dedup_store(seen)
     }
 }
 
@@ -48,108 +62,375 @@ impl<I> DedupHashAdapter for I where I: Iterator {}
 
 /* # dedup by */
 
-/// removes consecutive elements, whose equality is asserted by provided function
-#[derive(Debug, Clone)]
-pub struct DedupHashBy<I, F>
+/// removes elements, whose equality is asserted by provided function, remembering them in a `HashSet`
+///
+/// the function cannot be used for hashing, so every element is compared with all remembered ones;
+/// prefer `dedup_hash_by_equivalence` when a compatible hash is available
+pub type DedupHashBy<I, F, S = RandomState> = DedupStoreBy<I, HashSet<<I as Iterator>::Item, S>, F>;
+
+/// provides the `dedup_hash_by` method on `Iterator`s
+pub trait DedupHashByAdapter<F>: Iterator {
+    fn dedup_hash_by(self, equivalence: F) -> DedupHashBy<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item, &Self::Item) -> bool,
+    {
+        self.dedup_store_by(HashSet::new(), equivalence)
+    }
+
+    /// as `dedup_hash_by`, but treats the elements of `seen` as already yielded
+    fn dedup_hash_by_seeded<S>(
+        self,
+        seen: HashSet<Self::Item, S>,
+        equivalence: F,
+    ) -> DedupHashBy<Self, F, S>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item, &Self::Item) -> bool,
+    {
+        self.dedup_store_by(seen, equivalence)
+    }
+}
+
+impl<I, F> DedupHashByAdapter<F> for I where I: Iterator {}
+
+/* # dedup by key */
+
+/// removes elements, which give equal outputs from provided function, remembering the outputs in a `HashSet`
+pub type DedupHashByKey<I, F, K, S = RandomState> = DedupStoreByKey<I, HashSet<K, S>, F, K>;
+
+/// provides the `dedup_hash_by_key` method on `Iterator`s
+pub trait DedupHashByKeyAdapter<F, K>: Iterator {
+    fn dedup_hash_by_key(self, function: F) -> DedupHashByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(HashSet::new(), function)
+    }
+
+    /// as `dedup_hash_by_key`, but hashes the outputs with provided `BuildHasher`
+    fn dedup_hash_by_key_with_hasher<S>(
+        self,
+        function: F,
+        hash_builder: S,
+    ) -> DedupHashByKey<Self, F, K, S>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(HashSet::with_hasher(hash_builder), function)
+    }
+
+    /// as `dedup_hash_by_key_with_hasher`, but reserves room for `capacity` distinct outputs upfront,
+    /// e.g. for the lower bound of `size_hint`
+    fn dedup_hash_by_key_with_capacity_and_hasher<S>(
+        self,
+        capacity: usize,
+        function: F,
+        hash_builder: S,
+    ) -> DedupHashByKey<Self, F, K, S>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(
+            HashSet::with_capacity_and_hasher(capacity, hash_builder),
+            function,
+        )
+    }
+
+    /// as `dedup_hash_by_key`, but treats the outputs in `seen` as already yielded
+    fn dedup_hash_by_key_seeded<S>(
+        self,
+        seen: HashSet<K, S>,
+        function: F,
+    ) -> DedupHashByKey<Self, F, K, S>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(seen, function)
+    }
+}
+
+impl<I, F, K> DedupHashByKeyAdapter<F, K> for I where I: Iterator {}
+
+/* # try dedup */
+
+/// removes equal `Ok` elements, remembering them in a `HashSet`, and passes errors through
+pub type TryDedupHash<I, T, S = RandomState> = TryDedupStore<I, HashSet<T, S>>;
+
+/// provides the `try_dedup_hash` method on `Iterator`s of `Result`s
+pub trait TryDedupHashAdapter<T, E>: Iterator<Item = Result<T, E>> {
+    fn try_dedup_hash(self) -> TryDedupHash<Self, T>
+    where
+        Self: Sized,
+    {
+        self.try_dedup_store(HashSet::new())
+    }
+
+    /// as `try_dedup_hash`, but hashes the elements with provided `BuildHasher`
+    fn try_dedup_hash_with_hasher<S>(self, hash_builder: S) -> TryDedupHash<Self, T, S>
+    where
+        Self: Sized,
+    {
+        self.try_dedup_store(HashSet::with_hasher(hash_builder))
+    }
+}
+
+impl<I, T, E> TryDedupHashAdapter<T, E> for I where I: Iterator<Item = Result<T, E>> {}
+
+/* # try dedup by key */
+
+/// removes `Ok` elements, which give equal outputs from provided fallible function,
+/// remembering the outputs in a `HashSet`, and passes errors through
+pub type TryDedupHashByKey<I, F, K, S = RandomState> = TryDedupStoreByKey<I, HashSet<K, S>, F>;
+
+/// provides the `try_dedup_hash_by_key` method on `Iterator`s of `Result`s
+pub trait TryDedupHashByKeyAdapter<T, E, F, K>: Iterator<Item = Result<T, E>> {
+    fn try_dedup_hash_by_key(self, function: F) -> TryDedupHashByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&T) -> Result<K, E>,
+    {
+        self.try_dedup_store_by_key(HashSet::new(), function)
+    }
+
+    /// as `try_dedup_hash_by_key`, but hashes the outputs with provided `BuildHasher`
+    fn try_dedup_hash_by_key_with_hasher<S>(
+        self,
+        function: F,
+        hash_builder: S,
+    ) -> TryDedupHashByKey<Self, F, K, S>
+    where
+        Self: Sized,
+        F: Fn(&T) -> Result<K, E>,
+    {
+        self.try_dedup_store_by_key(HashSet::with_hasher(hash_builder), function)
+    }
+}
+
+impl<I, T, E, F, K> TryDedupHashByKeyAdapter<T, E, F, K> for I where I: Iterator<Item = Result<T, E>>
+{}
+
+/* # hash equivalence */
+
+/// an equivalence relation together with a hash function compatible with it
+///
+/// elements which are equivalent must hash the same way
+pub trait HashEquivalence<T> {
+    /// feeds the part of `item` relevant to the equivalence into `state`
+    fn hash<H>(&self, item: &T, state: &mut H)
+    where
+        H: Hasher;
+
+    /// checks whether `a` and `b` are equivalent
+    fn equivalent(&self, a: &T, b: &T) -> bool;
+}
+
+/// pairs a function, whose output is hashed, with an equivalence function
+impl<T, H, K, E> HashEquivalence<T> for (H, E)
 where
-    I: Iterator,
+    H: Fn(&T) -> K,
+    K: Hash,
+    E: Fn(&T, &T) -> bool,
 {
-    iterator: I,
-    seen: HashSet<I::Item>,
-    equivalence: F,
+    fn hash<S>(&self, item: &T, state: &mut S)
+    where
+        S: Hasher,
+    {
+        (self.0)(item).hash(state);
+    }
+
+    fn equivalent(&self, a: &T, b: &T) -> bool {
+        (self.1)(a, b)
+    }
 }
 
-impl<I, F> Iterator for DedupHashBy<I, F>
+/// the equivalence given by `Eq`, hashed with `Hash`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Equality;
+
+impl<T> HashEquivalence<T> for Equality
 where
-    I: Iterator,
-    I::Item: PartialEq + Eq + Hash + Clone,
-    F: Fn(&I::Item, &I::Item) -> bool,
+    T: Eq + Hash,
 {
-    type Item = I::Item;
+    fn hash<S>(&self, item: &T, state: &mut S)
+    where
+        S: Hasher,
+    {
+        item.hash(state);
+    }
 
-    fn next(&mut self) -> Option<I::Item> {
-        while let Some(item) = self.iterator.next() {
-            if !self.seen.iter().any(|old| (self.equivalence)(old, &item)) {
-                self.seen.insert(item.clone());
-                return Some(item);
-            }
-        }
-        None
+    fn equivalent(&self, a: &T, b: &T) -> bool {
+        a == b
     }
 }
 
-/// provides the `dedup_by` method on `Iterator`s
-pub trait DedupHashByAdapter<F>: Iterator {
-    fn dedup_hash_by(self, equivalence: F) -> DedupHashBy<Self, F>
+/// relates elements, which give equal outputs from provided function, computing the outputs whenever needed
+#[derive(Debug, Clone, Copy)]
+pub struct ByKey<F>(pub F);
+
+impl<T, F, K> HashEquivalence<T> for ByKey<F>
+where
+    F: Fn(&T) -> K,
+    K: Eq + Hash,
+{
+    fn hash<S>(&self, item: &T, state: &mut S)
     where
-        Self: Sized,
-        F: Fn(&Self::Item, &Self::Item) -> bool,
+        S: Hasher,
     {
-        DedupHashBy {
-            seen: HashSet::new(),
-            iterator: self,
+        (self.0)(item).hash(state);
+    }
+
+    fn equivalent(&self, a: &T, b: &T) -> bool {
+        (self.0)(a) == (self.0)(b)
+    }
+}
+
+/// marks the end of a bucket chain
+const NIL: usize = usize::MAX;
+
+/// a hash set, whose elements are hashed and compared through a `HashEquivalence`
+#[derive(Debug, Clone)]
+pub struct EquivalenceSet<T, E, S = RandomState> {
+    items: Vec<T>,
+    chain: Vec<usize>,
+    heads: HashMap<u64, usize>,
+    equivalence: E,
+    hash_builder: S,
+}
+
+impl<T, E> EquivalenceSet<T, E> {
+    pub fn new(equivalence: E) -> Self {
+        Self::with_hasher(equivalence, RandomState::new())
+    }
+}
+
+impl<T, E, S> EquivalenceSet<T, E, S> {
+    pub fn with_hasher(equivalence: E, hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, equivalence, hash_builder)
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, equivalence: E, hash_builder: S) -> Self {
+        Self {
+            items: Vec::with_capacity(capacity),
+            chain: Vec::with_capacity(capacity),
+            heads: HashMap::with_capacity(capacity),
             equivalence,
+            hash_builder,
         }
     }
-}
 
-impl<I, F> DedupHashByAdapter<F> for I where I: Iterator {}
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
 
-/* # dedup by key */
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
 
-/// removes consecutive elements, which give equal outputs from provided function
-#[derive(Debug, Clone)]
-pub struct DedupHashByKey<I, F, K>
-where
-    I: Iterator,
-    F: Fn(&I::Item) -> K,
-{
-    iterator: I,
-    seen: HashSet<K>,
-    function: F,
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.chain.clear();
+        self.heads.clear();
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.items.iter()
+    }
 }
 
-impl<I, F, K> Iterator for DedupHashByKey<I, F, K>
+impl<T, E, S> EquivalenceSet<T, E, S>
 where
-    I: Iterator,
-    F: Fn(&I::Item) -> K,
-    K: PartialEq + Eq + Hash,
+    E: HashEquivalence<T>,
+    S: BuildHasher,
 {
-    type Item = I::Item;
-
-    #[allow(clippy::while_let_on_iterator, reason = "seems more readable here")]
-    fn next(&mut self) -> Option<I::Item> {
-        while let Some(item) = self.iterator.next() {
-            let key = (self.function)(&item);
-            if !self.seen.contains(&key) {
-                self.seen.insert(key);
-                return Some(item);
+    fn hash(&self, item: &T) -> u64 {
+        let mut state = self.hash_builder.build_hasher();
+        self.equivalence.hash(item, &mut state);
+        state.finish()
+    }
+
+    fn find(&self, hash: u64, item: &T) -> Option<usize> {
+        let mut index = *self.heads.get(&hash)?;
+        while index != NIL {
+            if self.equivalence.equivalent(&self.items[index], item) {
+                return Some(index);
             }
+            index = self.chain[index];
         }
         None
     }
 }
 
-/// Provides the `dedup_by_key` method on `Iterator`s.
-pub trait DedupHashByKeyAdapter<F, K>: Iterator {
-    fn dedup_hash_by_key(self, function: F) -> DedupHashByKey<Self, F, K>
+impl<T, E, S> SeenStore<T> for EquivalenceSet<T, E, S>
+where
+    E: HashEquivalence<T>,
+    S: BuildHasher,
+{
+    fn contains(&self, item: &T) -> bool {
+        self.find(self.hash(item), item).is_some()
+    }
+
+    fn insert(&mut self, item: T) -> bool {
+        let hash = self.hash(&item);
+        if self.find(hash, &item).is_some() {
+            return false;
+        }
+        let index = self.items.len();
+        self.chain
+            .push(self.heads.insert(hash, index).unwrap_or(NIL));
+        self.items.push(item);
+        true
+    }
+
+    fn len(&self) -> usize {
+        EquivalenceSet::len(self)
+    }
+
+    fn clear(&mut self) {
+        EquivalenceSet::clear(self);
+    }
+}
+
+/* # dedup by equivalence */
+
+/// removes elements, which are equivalent under provided `HashEquivalence`, remembering them in an `EquivalenceSet`
+pub type DedupHashByEquivalence<I, E, S = RandomState> =
+    DedupStore<I, EquivalenceSet<<I as Iterator>::Item, E, S>>;
+
+/// provides the `dedup_hash_by_equivalence` method on `Iterator`s
+pub trait DedupHashByEquivalenceAdapter<E>: Iterator {
+    fn dedup_hash_by_equivalence(self, equivalence: E) -> DedupHashByEquivalence<Self, E>
     where
         Self: Sized,
-        F: Fn(&Self::Item) -> K,
+        E: HashEquivalence<Self::Item>,
     {
-        DedupHashByKey {
-            seen: HashSet::new(),
-            iterator: self,
-            function,
-        }
+        self.dedup_store(EquivalenceSet::new(equivalence))
+    }
+
+    /// as `dedup_hash_by_equivalence`, but hashes the elements with provided `BuildHasher`
+    fn dedup_hash_by_equivalence_with_hasher<S>(
+        self,
+        equivalence: E,
+        hash_builder: S,
+    ) -> DedupHashByEquivalence<Self, E, S>
+    where
+        Self: Sized,
+        E: HashEquivalence<Self::Item>,
+    {
+        self.dedup_store(EquivalenceSet::with_hasher(equivalence, hash_builder))
     }
 }
 
-impl<I, F, K> DedupHashByKeyAdapter<F, K> for I where I: Iterator {}
+impl<I, E> DedupHashByEquivalenceAdapter<E> for I where I: Iterator {}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hasher::FastState;
 
     #[test]
     fn deduplicate_empty_iterator() {
@@ -236,4 +517,100 @@ mod tests {
         let v = t.chars().dedup_hash_by_key(|_| 0_u8).collect::<String>();
         assert_eq!(&v, "a");
     }
+
+    #[test]
+    fn deduplicate_by_equivalence() {
+        let og = ["Raz", "dwa", "RAZ", "Trzy", "DWA", "trzy", "raz"];
+        let dp = og
+            .into_iter()
+            .dedup_hash_by_equivalence((
+                |word: &&str| word.to_lowercase(),
+                |a: &&str, b: &&str| a.eq_ignore_ascii_case(b),
+            ))
+            .collect::<Vec<_>>();
+        assert_eq!(dp, ["Raz", "dwa", "Trzy"]);
+    }
+
+    #[test]
+    fn deduplicate_by_equivalence_with_colliding_hash() {
+        let og = "ttu    teżż  czasem   jakkaś litterka     dwa  rrazy";
+        let dp = og
+            .chars()
+            .dedup_hash_by_equivalence((|_: &char| 0_u8, |a: &char, b: &char| a == b))
+            .collect::<String>();
+        assert_eq!(&dp, "tu eżczasmjkślirdwy");
+    }
+
+    #[test]
+    fn deduplicator_agrees_with_adapter() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let mut dd = Deduplicator::new();
+        let dp = og.chars().filter(|&c| dd.insert(c)).collect::<String>();
+        assert_eq!(dp, og.chars().dedup_hash().collect::<String>());
+        assert_eq!(dd.len(), dp.chars().count());
+    }
+
+    #[test]
+    fn dedup_by_key_seeded() {
+        let og = [(1, 'a'), (2, 'b'), (3, 'c'), (2, 'd')];
+        let mut dp = og
+            .into_iter()
+            .dedup_hash_by_key_seeded(HashSet::from([1]), |pair| pair.0);
+        assert_eq!(dp.by_ref().collect::<Vec<_>>(), [(2, 'b'), (3, 'c')]);
+        assert_eq!(dp.into_seen().len(), 3);
+    }
+
+    #[test]
+    fn deduplicate_with_fast_hasher() {
+        let og: [i32; 7] = [10, 20, 20, 21, 30, 30, 20];
+        let iter = og.into_iter();
+        let capacity = iter.size_hint().0;
+        let dp = iter.dedup_hash_with_capacity_and_hasher(capacity, FastState::new());
+        assert!(dp.seen().capacity() >= og.len());
+        assert_eq!(dp.collect::<Vec<_>>(), [10, 20, 21, 30]);
+    }
+
+    #[test]
+    fn seeded_hasher_is_reproducible() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let order = |seed| {
+            let mut dp = og.chars().dedup_hash_by_key_with_hasher(
+                |c| c.to_ascii_uppercase(),
+                FastState::with_seed(seed),
+            );
+            dp.by_ref().for_each(drop);
+            dp.into_seen().into_iter().collect::<String>()
+        };
+        assert_eq!(order(7), order(7));
+        assert_eq!(order(7).len(), "spor dwjnychlite".len());
+    }
+
+    #[test]
+    fn try_dedup_hash_never_remembers_errors() {
+        let og = [Ok(10), Err(1), Ok(20), Ok(10), Err(1), Ok(20), Ok(30)];
+        let mut dp = og.into_iter().try_dedup_hash();
+        assert_eq!(
+            dp.by_ref().collect::<Vec<_>>(),
+            [Ok(10), Err(1), Ok(20), Err(1), Ok(30)]
+        );
+        assert_eq!(dp.seen().len(), 3);
+        let dp = og.into_iter().try_dedup_hash().short_circuit();
+        assert_eq!(dp.collect::<Vec<_>>(), [Ok(10), Err(1)]);
+    }
+
+    #[test]
+    fn try_dedup_hash_by_key_with_failing_key() {
+        let og = "raz\ndwa\nRAZ\n\ntrzy\nDwa";
+        let dp = og
+            .lines()
+            .map(Ok)
+            .try_dedup_hash_by_key(|line: &&str| {
+                line.chars()
+                    .next()
+                    .map(|c| c.to_ascii_lowercase())
+                    .ok_or("pusta linia")
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [Ok("raz"), Ok("dwa"), Err("pusta linia"), Ok("trzy")]);
+    }
 }
@@ -0,0 +1,159 @@
+use core::hash::{BuildHasher, Hasher};
+
+/* # fast hasher */
+
+/// multiplier spreading every written word over the state
+const MULTIPLIER: u64 = 0xf135_7aea_2e62_a9c5;
+
+/// a fast, non-cryptographic hasher, well suited to integer identifiers
+///
+/// it offers no protection against collisions crafted on purpose, so it should only be used on trusted input
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FastHasher {
+    hash: u64,
+}
+
+impl FastHasher {
+    pub fn with_seed(seed: u64) -> Self {
+        Self { hash: seed }
+    }
+
+    fn add(&mut self, word: u64) {
+        self.hash = self.hash.wrapping_add(word).wrapping_mul(MULTIPLIER);
+    }
+}
+
+impl Hasher for FastHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in chunks.by_ref() {
+            self.add(u64::from_le_bytes(
+                chunk.try_into().expect("chunk of eight bytes"),
+            ));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add(u64::from_le_bytes(word) ^ ((rest.len() as u64) << 56));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.add(i as u64);
+        self.add((i >> 64) as u64);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    /// mixes the state, so that every input bit affects every output bit
+    fn finish(&self) -> u64 {
+        let mut z = self.hash;
+        z = (z ^ (z >> 33)).wrapping_mul(0xff51_afd7_ed55_8ccd);
+        z = (z ^ (z >> 33)).wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        z ^ (z >> 33)
+    }
+}
+
+/* # fast state */
+
+/// builds `FastHasher`s from a fixed seed
+///
+/// unlike `RandomState`, the same seed gives the same hashes in every run, which makes runs reproducible
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FastState {
+    seed: u64,
+}
+
+impl FastState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl BuildHasher for FastState {
+    type Hasher = FastHasher;
+
+    fn build_hasher(&self) -> FastHasher {
+        FastHasher::with_seed(self.seed)
+    }
+}
+
+/* # splitmix */
+
+/// the step between consecutive states of `splitmix`
+pub(crate) const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// scrambles `state` moved on by `GAMMA`, so that consecutive states give unrelated outputs
+pub(crate) fn splitmix(state: u64) -> u64 {
+    let mut z = state.wrapping_add(GAMMA);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_same_hash() {
+        let one = FastState::with_seed(7);
+        let two = FastState::with_seed(7);
+        assert_eq!(one.hash_one("ssporrro"), two.hash_one("ssporrro"));
+        assert_eq!(one.hash_one(12_u64), two.hash_one(12_u64));
+    }
+
+    #[test]
+    fn different_seed_gives_different_hash() {
+        let one = FastState::with_seed(7);
+        let two = FastState::with_seed(8);
+        assert_ne!(one.hash_one("ssporrro"), two.hash_one("ssporrro"));
+    }
+
+    #[test]
+    fn byte_strings_of_different_length_differ() {
+        let state = FastState::new();
+        assert_ne!(state.hash_one([0_u8; 3]), state.hash_one([0_u8; 4]));
+        let mut short = state.build_hasher();
+        short.write(&[0; 7]);
+        let mut long = state.build_hasher();
+        long.write(&[0; 8]);
+        assert_ne!(short.finish(), long.finish());
+    }
+
+    #[test]
+    fn consecutive_integers_spread_over_high_bits() {
+        let state = FastState::new();
+        let buckets = (0_u32..1024).map(|n| state.hash_one(n) >> 60).fold(
+            [0_u32; 16],
+            |mut buckets, bucket| {
+                buckets[bucket as usize] += 1;
+                buckets
+            },
+        );
+        assert!(buckets.iter().all(|&count| (32..=96).contains(&count)));
+    }
+}
@@ -0,0 +1,269 @@
+use std::hash::{BuildHasher, Hash};
+
+use crate::hasher::FastState;
+
+/* # hyperloglog */
+
+/// the precision of sketches built by `approx_distinct`, with a standard error of about 0.8% in 16 KiB
+pub const DEFAULT_PRECISION: u8 = 14;
+
+/// estimates the number of distinct elements inserted, in `2^precision` bytes
+///
+/// the standard error is about `1.04 / 2^(precision / 2)`; sketches built with equal precision and hashers
+/// can be merged, e.g. ones counted in parallel, which is why the default hasher is the deterministic `FastState`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog<S = FastState> {
+    registers: Vec<u8>,
+    precision: u8,
+    hash_builder: S,
+}
+
+impl HyperLogLog {
+    pub fn new(precision: u8) -> Self {
+        Self::with_hasher(precision, FastState::new())
+    }
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_PRECISION)
+    }
+}
+
+impl<S> HyperLogLog<S> {
+    /// as `new`, but hashes the elements with provided `BuildHasher`
+    pub fn with_hasher(precision: u8, hash_builder: S) -> Self {
+        assert!(
+            (4..=18).contains(&precision),
+            "precision must lie between 4 and 18"
+        );
+        Self {
+            registers: vec![0; 1 << precision],
+            precision,
+            hash_builder,
+        }
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// checks whether no element has been inserted
+    pub fn is_empty(&self) -> bool {
+        self.registers.iter().all(|&register| register == 0)
+    }
+
+    /// estimates the number of distinct elements inserted
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum = self
+            .registers
+            .iter()
+            .map(|&register| (-f64::from(register)).exp2())
+            .sum::<f64>();
+        let raw = alpha * m * m / sum;
+        let zeros = self
+            .registers
+            .iter()
+            .filter(|&&register| register == 0)
+            .count();
+        if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        }
+    }
+
+    /// adds the elements counted by `other`, so that the estimate is that of their union
+    ///
+    /// panics if the sketches differ in precision, and gives meaningless estimates if they differ in hashers
+    pub fn merge(&mut self, other: &Self) {
+        assert_eq!(
+            self.precision, other.precision,
+            "only sketches of equal precision can be merged"
+        );
+        for (register, &theirs) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(theirs);
+        }
+    }
+
+    /// forgets all inserted elements
+    pub fn clear(&mut self) {
+        self.registers.iter_mut().for_each(|register| *register = 0);
+    }
+}
+
+impl<S> HyperLogLog<S>
+where
+    S: BuildHasher,
+{
+    pub fn insert<T>(&mut self, item: &T)
+    where
+        T: Hash + ?Sized,
+    {
+        let hash = self.hash_builder.hash_one(item);
+        let index = (hash >> (64 - self.precision)) as usize;
+        let rest = hash << self.precision;
+        let rank = rest.leading_zeros().min(64 - u32::from(self.precision)) + 1;
+        self.registers[index] = self.registers[index].max(rank as u8);
+    }
+}
+
+impl<T, S> Extend<T> for HyperLogLog<S>
+where
+    T: Hash,
+    S: BuildHasher,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        iter.into_iter().for_each(|item| self.insert(&item));
+    }
+}
+
+/* # approx distinct */
+
+/// provides the `approx_distinct` method on `Iterator`s
+pub trait ApproxDistinctAdapter: Iterator {
+    /// counts the elements into a sketch of `DEFAULT_PRECISION`, instead of deduplicating them
+    fn approx_distinct(self) -> HyperLogLog
+    where
+        Self: Sized,
+        Self::Item: Hash,
+    {
+        self.approx_distinct_with_precision(DEFAULT_PRECISION)
+    }
+
+    /// as `approx_distinct`, but with provided precision
+    fn approx_distinct_with_precision(self, precision: u8) -> HyperLogLog
+    where
+        Self: Sized,
+        Self::Item: Hash,
+    {
+        self.approx_distinct_with_hasher(precision, FastState::new())
+    }
+
+    /// as `approx_distinct_with_precision`, hashing the elements with provided `BuildHasher`
+    fn approx_distinct_with_hasher<S>(self, precision: u8, hash_builder: S) -> HyperLogLog<S>
+    where
+        Self: Sized,
+        Self::Item: Hash,
+        S: BuildHasher,
+    {
+        let mut sketch = HyperLogLog::with_hasher(precision, hash_builder);
+        sketch.extend(self);
+        sketch
+    }
+}
+
+impl<I> ApproxDistinctAdapter for I where I: Iterator {}
+
+/* # approx distinct by key */
+
+/// provides the `approx_distinct_by_key` method on `Iterator`s
+pub trait ApproxDistinctByKeyAdapter<F, K>: Iterator {
+    /// counts distinct outputs from provided function into a sketch of `DEFAULT_PRECISION`
+    fn approx_distinct_by_key(self, function: F) -> HyperLogLog
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+        K: Hash,
+    {
+        self.approx_distinct_by_key_with_hasher(DEFAULT_PRECISION, function, FastState::new())
+    }
+
+    /// as `approx_distinct_by_key`, but with provided precision, hashing the outputs with provided `BuildHasher`
+    fn approx_distinct_by_key_with_hasher<S>(
+        self,
+        precision: u8,
+        function: F,
+        hash_builder: S,
+    ) -> HyperLogLog<S>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+        K: Hash,
+        S: BuildHasher,
+    {
+        let mut sketch = HyperLogLog::with_hasher(precision, hash_builder);
+        sketch.extend(self.map(|item| function(&item)));
+        sketch
+    }
+}
+
+impl<I, F, K> ApproxDistinctByKeyAdapter<F, K> for I where I: Iterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(estimate: f64, exact: usize, error: f64) {
+        let exact = exact as f64;
+        assert!((estimate - exact).abs() <= exact * error);
+    }
+
+    #[test]
+    fn estimate_empty_iterator() {
+        let og = Vec::<u8>::new();
+        let sketch = og.iter().approx_distinct();
+        assert!(sketch.is_empty());
+        assert_eq!(sketch.estimate(), 0.0);
+    }
+
+    #[test]
+    fn count_distinct_characters() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let estimate = og.chars().approx_distinct().estimate();
+        assert_eq!(estimate.round(), "spor dwjnychlite".len() as f64);
+    }
+
+    #[test]
+    fn estimate_large_stream() {
+        let og = (0..300_000_u32).map(|n| n % 100_000);
+        assert_close(og.approx_distinct().estimate(), 100_000, 0.03);
+        let og = (0..300_000_u32).map(|n| n % 100_000);
+        assert_close(
+            og.approx_distinct_with_precision(10).estimate(),
+            100_000,
+            0.1,
+        );
+    }
+
+    #[test]
+    fn merge_counts_union() {
+        let mut one = (0..60_000_u32).approx_distinct();
+        let two = (40_000..100_000_u32).approx_distinct();
+        one.merge(&two);
+        assert_close(one.estimate(), 100_000, 0.03);
+        let mut seeded = (0..60_000_u32).approx_distinct_with_hasher(12, FastState::with_seed(7));
+        seeded
+            .merge(&(40_000..100_000_u32).approx_distinct_with_hasher(12, FastState::with_seed(7)));
+        assert_close(seeded.estimate(), 100_000, 0.05);
+        assert_eq!(one, (0..100_000_u32).approx_distinct());
+        one.clear();
+        assert!(one.is_empty());
+    }
+
+    #[test]
+    fn count_distinct_by_key() {
+        let og = ["Raz", "dwa", "RAZ", "Trzy", "DWA", "trzy", "raz"];
+        let sketch = og
+            .into_iter()
+            .approx_distinct_by_key(|word| word.to_lowercase());
+        assert_eq!(sketch.estimate().round(), 3.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "equal precision")]
+    fn merge_needs_equal_precision() {
+        let mut one = HyperLogLog::new(10);
+        one.merge(&HyperLogLog::new(12));
+    }
+}
@@ -1,6 +1,29 @@
-#![feature(lint_reasons)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod bloom;
+#[cfg(feature = "std")]
+pub mod cuckoo;
 pub mod dedup;
+pub mod duplicates;
+#[cfg(feature = "std")]
+pub mod fingerprint;
+#[cfg(feature = "std")]
 pub mod hashable;
+pub mod hasher;
+#[cfg(feature = "std")]
+pub mod hyperloglog;
+#[cfg(feature = "std")]
+pub mod lru;
 pub mod noncon;
 pub mod ordable;
+#[cfg(feature = "std")]
+pub mod slice;
+pub mod store;
+#[cfg(feature = "std")]
+pub mod ttl;
+pub mod unique;
+#[cfg(feature = "std")]
+pub mod window;
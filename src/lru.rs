@@ -0,0 +1,282 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, Hash},
+    mem,
+};
+
+use crate::store::{
+    DedupStore, DedupStoreAdapter, DedupStoreByKey, DedupStoreByKeyAdapter, SeenStore,
+};
+
+/* # lru store */
+
+/// marks a missing neighbour
+const NIL: usize = usize::MAX;
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    item: T,
+    newer: usize,
+    older: usize,
+}
+
+/// remembers up to `capacity` distinct elements, evicting the least recently seen one to make room for a new one
+///
+/// elements live in an arena of nodes linked from the most to the least recently seen one,
+/// and are found through a `HashMap` of their positions, so every operation takes constant time
+#[derive(Debug, Clone)]
+pub struct LruStore<T, S = RandomState> {
+    capacity: usize,
+    positions: HashMap<T, usize, S>,
+    nodes: Vec<Node<T>>,
+    newest: usize,
+    oldest: usize,
+    evictions: usize,
+}
+
+impl<T> LruStore<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self::with_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<T, S> LruStore<T, S> {
+    /// as `new`, but hashes the elements with provided `BuildHasher`
+    pub fn with_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            capacity,
+            positions: HashMap::with_hasher(hash_builder),
+            nodes: Vec::new(),
+            newest: NIL,
+            oldest: NIL,
+            evictions: 0,
+        }
+    }
+
+    /// the largest number of elements remembered at once
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// the number of elements evicted since the store was created
+    pub fn evictions(&self) -> usize {
+        self.evictions
+    }
+
+    /// iterates over remembered elements, from the most to the least recently seen one
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let mut index = self.newest;
+        core::iter::from_fn(move || {
+            let node = self.nodes.get(index)?;
+            index = node.older;
+            Some(&node.item)
+        })
+    }
+
+    fn unlink(&mut self, index: usize) {
+        let Node { newer, older, .. } = self.nodes[index];
+        match newer {
+            NIL => self.newest = older,
+            newer => self.nodes[newer].older = older,
+        }
+        match older {
+            NIL => self.oldest = newer,
+            older => self.nodes[older].newer = newer,
+        }
+    }
+
+    fn link_newest(&mut self, index: usize) {
+        self.nodes[index].newer = NIL;
+        self.nodes[index].older = self.newest;
+        match self.newest {
+            NIL => self.oldest = index,
+            newest => self.nodes[newest].newer = index,
+        }
+        self.newest = index;
+    }
+
+    /// marks the element at `index` as the most recently seen one
+    fn touch(&mut self, index: usize) {
+        if index != self.newest {
+            self.unlink(index);
+            self.link_newest(index);
+        }
+    }
+}
+
+impl<T, S> SeenStore<T> for LruStore<T, S>
+where
+    T: Clone + Eq + Hash,
+    S: BuildHasher,
+{
+    fn contains(&self, item: &T) -> bool {
+        self.positions.contains_key(item)
+    }
+
+    fn observe(&mut self, item: &T) -> bool {
+        match self.positions.get(item) {
+            Some(&index) => {
+                self.touch(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn insert(&mut self, item: T) -> bool {
+        if self.observe(&item) {
+            return false;
+        }
+        if self.capacity == 0 {
+            return true;
+        }
+        let index = if self.nodes.len() < self.capacity {
+            self.nodes.push(Node {
+                item: item.clone(),
+                newer: NIL,
+                older: NIL,
+            });
+            self.nodes.len() - 1
+        } else {
+            let index = self.oldest;
+            self.unlink(index);
+            let evicted = mem::replace(&mut self.nodes[index].item, item.clone());
+            self.positions.remove(&evicted);
+            self.evictions += 1;
+            index
+        };
+        self.positions.insert(item, index);
+        self.link_newest(index);
+        true
+    }
+
+    fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    fn clear(&mut self) {
+        self.positions.clear();
+        self.nodes.clear();
+        self.newest = NIL;
+        self.oldest = NIL;
+    }
+}
+
+/* # dedup lru */
+
+/// removes elements equal to one of the `capacity` most recently seen distinct elements
+pub type DedupHashLru<I, S = RandomState> = DedupStore<I, LruStore<<I as Iterator>::Item, S>>;
+
+/// provides the `dedup_hash_lru` method on `Iterator`s
+pub trait DedupHashLruAdapter: Iterator {
+    fn dedup_hash_lru(self, capacity: usize) -> DedupHashLru<Self>
+    where
+        Self: Sized,
+    {
+        self.dedup_store(LruStore::new(capacity))
+    }
+
+    /// as `dedup_hash_lru`, but hashes the elements with provided `BuildHasher`
+    fn dedup_hash_lru_with_hasher<S>(
+        self,
+        capacity: usize,
+        hash_builder: S,
+    ) -> DedupHashLru<Self, S>
+    where
+        Self: Sized,
+    {
+        self.dedup_store(LruStore::with_hasher(capacity, hash_builder))
+    }
+}
+
+impl<I> DedupHashLruAdapter for I where I: Iterator {}
+
+/* # dedup lru by key */
+
+/// removes elements, whose output from provided function equals one of the `capacity` most recently seen outputs
+pub type DedupHashLruByKey<I, F, K, S = RandomState> = DedupStoreByKey<I, LruStore<K, S>, F, K>;
+
+/// provides the `dedup_hash_lru_by_key` method on `Iterator`s
+pub trait DedupHashLruByKeyAdapter<F, K>: Iterator {
+    fn dedup_hash_lru_by_key(self, capacity: usize, function: F) -> DedupHashLruByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(LruStore::new(capacity), function)
+    }
+
+    /// as `dedup_hash_lru_by_key`, but hashes the outputs with provided `BuildHasher`
+    fn dedup_hash_lru_by_key_with_hasher<S>(
+        self,
+        capacity: usize,
+        function: F,
+        hash_builder: S,
+    ) -> DedupHashLruByKey<Self, F, K, S>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(LruStore::with_hasher(capacity, hash_builder), function)
+    }
+}
+
+impl<I, F, K> DedupHashLruByKeyAdapter<F, K> for I where I: Iterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Deduplicator;
+
+    #[test]
+    fn deduplicate_empty_iterator() {
+        let og = Vec::<u8>::new();
+        let dp = og.iter().dedup_hash_lru(3).count();
+        assert_eq!(dp, 0);
+    }
+
+    #[test]
+    fn large_capacity_matches_dedup_hash() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og.chars().dedup_hash_lru(64).collect::<String>();
+        assert_eq!(&dp, "spor dwjnychlite");
+    }
+
+    #[test]
+    fn least_recently_seen_is_evicted() {
+        let og: [i32; 8] = [10, 20, 10, 30, 20, 10, 30, 20];
+        let mut dp = og.into_iter().dedup_hash_lru(2);
+        assert_eq!(
+            dp.by_ref().collect::<Vec<_>>(),
+            [10, 20, 30, 20, 10, 30, 20]
+        );
+        assert_eq!(dp.seen().evictions(), 5);
+        assert_eq!(dp.seen().iter().collect::<Vec<_>>(), [&20, &30]);
+    }
+
+    #[test]
+    fn duplicate_refreshes_recency() {
+        let og = ["raz", "dwa", "raz", "trzy", "raz", "dwa"];
+        let mut dp = og
+            .into_iter()
+            .dedup_hash_lru_by_key(2, |word| word.to_uppercase());
+        assert_eq!(
+            dp.by_ref().collect::<Vec<_>>(),
+            ["raz", "dwa", "trzy", "dwa"]
+        );
+        assert_eq!(dp.into_seen().evictions(), 2);
+    }
+
+    #[test]
+    fn deduplicator_with_lru_store() {
+        let mut dd = Deduplicator::with_store(LruStore::new(1));
+        assert!(dd.insert('a'));
+        assert!(!dd.insert('a'));
+        assert!(dd.insert('b'));
+        assert!(dd.would_emit(&'a'));
+        assert_eq!(dd.len(), 1);
+        assert_eq!(dd.seen().evictions(), 1);
+        dd.clear();
+        assert!(dd.is_empty());
+    }
+}
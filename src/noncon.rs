@@ -1,44 +1,36 @@
-/* # dedup */
+use alloc::vec::Vec;
+use core::fmt;
 
-/// removes consecutive equal elements
-#[derive(Debug, Clone)]
-pub struct DedupNonCon<I>
-where
-    I: Iterator,
-{
-    iterator: I,
-    seen: Vec<I::Item>,
-}
+use crate::store::{
+    self, DedupStore, DedupStoreAdapter, DedupStoreBy, DedupStoreByAdapter, DedupStoreByKey,
+    DedupStoreByKeyAdapter, SeenStore,
+};
 
-impl<I> Iterator for DedupNonCon<I>
-where
-    I: Iterator,
-    I::Item: PartialEq + Clone,
-{
-    type Item = I::Item;
+/* # deduplicator */
 
-    #[allow(clippy::while_let_on_iterator, reason = "seems more readable here")]
-    fn next(&mut self) -> Option<I::Item> {
-        while let Some(item) = self.iterator.next() {
-            if !self.seen.contains(&item) {
-                self.seen.push(item.clone());
-                return Some(item);
-            }
-        }
-        None
-    }
-}
+/// removes equal elements pushed one at a time, remembering them in a `Vec`
+pub type Deduplicator<T> = store::Deduplicator<T, Vec<T>>;
 
-/// provides the `dedup` method on `Iterator`s
+/* # dedup */
+
+/// removes equal elements, remembering them in a `Vec`
+pub type DedupNonCon<I> = DedupStore<I, Vec<<I as Iterator>::Item>>;
+
+/// provides the `dedup_non_con` method on `Iterator`s
 pub trait DedupNonConAdapter: Iterator {
     fn dedup_non_con(self) -> DedupNonCon<Self>
     where
         Self: Sized,
     {
-        DedupNonCon {
-            seen: Vec::new(),
-            iterator: self,
-        }
+        self.dedup_store(Vec::new())
+    }
+
+    /// as `dedup_non_con`, but treats the elements of `seen` as already yielded
+    fn dedup_non_con_seeded(self, seen: Vec<Self::Item>) -> DedupNonCon<Self>
+    where
+        Self: Sized,
+    {
+        self.dedup_store(seen)
     }
 }
 
@@ -46,105 +38,264 @@ impl<I> DedupNonConAdapter for I where I: Iterator {}
 
 /* # dedup by */
 
-/// removes consecutive elements, whose equality is asserted by provided function
+/// removes elements, whose equality is asserted by provided function, remembering them in a `Vec`
+pub type DedupNonConBy<I, F> = DedupStoreBy<I, Vec<<I as Iterator>::Item>, F>;
+
+/// provides the `dedup_non_con_by` method on `Iterator`s
+pub trait DedupNonConByAdapter<F>: Iterator {
+    fn dedup_non_con_by(self, equivalence: F) -> DedupNonConBy<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item, &Self::Item) -> bool,
+    {
+        self.dedup_store_by(Vec::new(), equivalence)
+    }
+
+    /// as `dedup_non_con_by`, but treats the elements of `seen` as already yielded
+    fn dedup_non_con_by_seeded(
+        self,
+        seen: Vec<Self::Item>,
+        equivalence: F,
+    ) -> DedupNonConBy<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item, &Self::Item) -> bool,
+    {
+        self.dedup_store_by(seen, equivalence)
+    }
+}
+
+impl<I, F> DedupNonConByAdapter<F> for I where I: Iterator {}
+
+/* # dedup by key */
+
+/// removes elements, which give equal outputs from provided function, remembering the outputs in a `Vec`
+pub type DedupNonConByKey<I, F, K> = DedupStoreByKey<I, Vec<K>, F, K>;
+
+/// provides the `dedup_non_con_by_key` method on `Iterator`s
+pub trait DedupNonConByKeyAdapter<F, K>: Iterator {
+    fn dedup_non_con_by_key(self, function: F) -> DedupNonConByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(Vec::new(), function)
+    }
+
+    /// as `dedup_non_con_by_key`, but treats the outputs in `seen` as already yielded
+    fn dedup_non_con_by_key_seeded(self, seen: Vec<K>, function: F) -> DedupNonConByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(seen, function)
+    }
+}
+
+impl<I, F, K> DedupNonConByKeyAdapter<F, K> for I where I: Iterator {}
+
+/* # fixed store */
+
+/// what a full `FixedStore` does with a new element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// forgets the oldest element to make room for the new one
+    EvictOldest,
+    /// lets the new element through without remembering it
+    PassThrough,
+}
+
+/// returned when a new element does not fit into a full `FixedStore`, handing the element back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError<T>(pub T);
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("fixed store is full")
+    }
+}
+
+impl<T> core::error::Error for CapacityError<T> where T: fmt::Debug {}
+
+/// remembers up to `N` elements inline, without allocating
+///
+/// elements are scanned linearly, as in a `Vec`, and kept in the order they were inserted
 #[derive(Debug, Clone)]
-pub struct DedupNonConBy<I, F>
-where
-    I: Iterator,
-{
-    iterator: I,
-    seen: Vec<I::Item>,
-    equivalence: F,
+pub struct FixedStore<T, const N: usize> {
+    items: [Option<T>; N],
+    oldest: usize,
+    len: usize,
+    overflow: Overflow,
 }
 
-impl<I, F> Iterator for DedupNonConBy<I, F>
+impl<T, const N: usize> FixedStore<T, N> {
+    pub fn new(overflow: Overflow) -> Self {
+        Self {
+            items: core::array::from_fn(|_| None),
+            oldest: 0,
+            len: 0,
+            overflow,
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// iterates over remembered elements, from the oldest to the newest
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.len).filter_map(|offset| self.items[(self.oldest + offset) % N].as_ref())
+    }
+
+    /// records `item` if there is room left, regardless of the overflow policy
+    pub fn try_insert(&mut self, item: T) -> Result<bool, CapacityError<T>>
+    where
+        T: PartialEq,
+    {
+        if self.iter().any(|old| old == &item) {
+            return Ok(false);
+        }
+        if self.is_full() {
+            return Err(CapacityError(item));
+        }
+        self.items[(self.oldest + self.len) % N] = Some(item);
+        self.len += 1;
+        Ok(true)
+    }
+}
+
+impl<T, const N: usize> SeenStore<T> for FixedStore<T, N>
 where
-    I: Iterator,
-    I::Item: Clone,
-    F: Fn(&I::Item, &I::Item) -> bool,
+    T: PartialEq,
 {
-    type Item = I::Item;
+    fn contains(&self, item: &T) -> bool {
+        self.iter().any(|old| old == item)
+    }
 
-    fn next(&mut self) -> Option<I::Item> {
-        while let Some(item) = self.iterator.next() {
-            if !self.seen.iter().any(|old| (self.equivalence)(old, &item)) {
-                self.seen.push(item.clone());
-                return Some(item);
+    fn insert(&mut self, item: T) -> bool {
+        match self.try_insert(item) {
+            Ok(new) => new,
+            Err(CapacityError(item)) => {
+                if self.overflow == Overflow::EvictOldest && N > 0 {
+                    self.items[self.oldest] = Some(item);
+                    self.oldest = (self.oldest + 1) % N;
+                }
+                true
             }
         }
-        None
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        self.items.iter_mut().for_each(|item| *item = None);
+        self.oldest = 0;
+        self.len = 0;
     }
 }
 
-/// provides the `dedup_by` method on `Iterator`s
-pub trait DedupNonConByAdapter<F>: Iterator {
-    fn dedup_non_con_by(self, equivalence: F) -> DedupNonConBy<Self, F>
+/* # dedup fixed */
+
+/// removes equal elements, remembering up to `N` of them inline
+pub type DedupNonConFixed<I, const N: usize> = DedupStore<I, FixedStore<<I as Iterator>::Item, N>>;
+
+/// removes elements, which give equal outputs from provided function, remembering up to `N` outputs inline
+pub type DedupNonConFixedByKey<I, F, K, const N: usize> =
+    DedupStoreByKey<I, FixedStore<K, N>, F, K>;
+
+/// provides the `dedup_non_con_fixed` methods on `Iterator`s
+pub trait DedupNonConFixedAdapter: Iterator {
+    fn dedup_non_con_fixed<const N: usize>(self, overflow: Overflow) -> DedupNonConFixed<Self, N>
     where
         Self: Sized,
-        F: Fn(&Self::Item, &Self::Item) -> bool,
     {
-        DedupNonConBy {
-            seen: Vec::new(),
+        self.dedup_store(FixedStore::new(overflow))
+    }
+
+    fn dedup_non_con_fixed_by_key<F, K, const N: usize>(
+        self,
+        overflow: Overflow,
+        function: F,
+    ) -> DedupNonConFixedByKey<Self, F, K, N>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(FixedStore::new(overflow), function)
+    }
+
+    /// as `dedup_non_con_fixed`, but yields a `CapacityError` for every new element, which does not fit
+    fn dedup_non_con_fixed_checked<const N: usize>(self) -> DedupNonConFixedChecked<Self, N>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        self.dedup_non_con_fixed_checked_by_key(Self::Item::clone)
+    }
+
+    /// as `dedup_non_con_fixed_by_key`, but yields a `CapacityError` for every new element, whose output does not fit
+    fn dedup_non_con_fixed_checked_by_key<F, K, const N: usize>(
+        self,
+        function: F,
+    ) -> DedupNonConFixedCheckedByKey<Self, F, K, N>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        DedupNonConFixedCheckedByKey {
             iterator: self,
-            equivalence,
+            seen: FixedStore::new(Overflow::PassThrough),
+            function,
         }
     }
 }
 
-impl<I, F> DedupNonConByAdapter<F> for I where I: Iterator {}
+impl<I> DedupNonConFixedAdapter for I where I: Iterator {}
 
-/* # dedup by key */
+/// removes equal elements, remembering up to `N` of them inline, and reports elements which do not fit
+pub type DedupNonConFixedChecked<I, const N: usize> = DedupNonConFixedCheckedByKey<
+    I,
+    fn(&<I as Iterator>::Item) -> <I as Iterator>::Item,
+    <I as Iterator>::Item,
+    N,
+>;
 
-/// removes consecutive elements, which give equal outputs from provided function
+/// removes elements, which give equal outputs from provided function, remembering up to `N` outputs inline,
+/// and reports elements whose outputs do not fit
 #[derive(Debug, Clone)]
-pub struct DedupNonConByKey<I, F, K>
+pub struct DedupNonConFixedCheckedByKey<I, F, K, const N: usize>
 where
     I: Iterator,
     F: Fn(&I::Item) -> K,
 {
     iterator: I,
-    seen: Vec<K>,
+    seen: FixedStore<K, N>,
     function: F,
 }
 
-impl<I, F, K> Iterator for DedupNonConByKey<I, F, K>
+impl<I, F, K, const N: usize> Iterator for DedupNonConFixedCheckedByKey<I, F, K, N>
 where
     I: Iterator,
     F: Fn(&I::Item) -> K,
     K: PartialEq,
 {
-    type Item = I::Item;
+    type Item = Result<I::Item, CapacityError<I::Item>>;
 
     #[allow(clippy::while_let_on_iterator, reason = "seems more readable here")]
-    fn next(&mut self) -> Option<I::Item> {
+    fn next(&mut self) -> Option<Self::Item> {
         while let Some(item) = self.iterator.next() {
-            let key = (self.function)(&item);
-            if !self.seen.contains(&key) {
-                self.seen.push(key);
-                return Some(item);
+            match self.seen.try_insert((self.function)(&item)) {
+                Ok(true) => return Some(Ok(item)),
+                Ok(false) => continue,
+                Err(_) => return Some(Err(CapacityError(item))),
             }
         }
         None
     }
 }
 
-/// Provides the `dedup_by_key` method on `Iterator`s.
-pub trait DedupNonConByKeyAdapter<F, K>: Iterator {
-    fn dedup_non_con_by_key(self, function: F) -> DedupNonConByKey<Self, F, K>
-    where
-        Self: Sized,
-        F: Fn(&Self::Item) -> K,
-    {
-        DedupNonConByKey {
-            seen: Vec::new(),
-            iterator: self,
-            function,
-        }
-    }
-}
-
-impl<I, F, K> DedupNonConByKeyAdapter<F, K> for I where I: Iterator {}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -234,4 +385,69 @@ mod tests {
         let v = t.chars().dedup_non_con_by_key(|_| 0_u8).collect::<String>();
         assert_eq!(&v, "a");
     }
+
+    #[test]
+    fn dedup_by_key_seeded() {
+        let og = [(1, 'a'), (2, 'b'), (3, 'c'), (2, 'd')];
+        let mut dp = og
+            .into_iter()
+            .dedup_non_con_by_key_seeded(Vec::from([1]), |pair| pair.0);
+        assert_eq!(dp.by_ref().collect::<Vec<_>>(), [(2, 'b'), (3, 'c')]);
+        assert_eq!(dp.into_seen().len(), 3);
+    }
+
+    #[test]
+    fn fixed_evicts_oldest() {
+        let og = [1, 2, 3, 1, 4, 2, 1];
+        let dp = og
+            .into_iter()
+            .dedup_non_con_fixed::<3>(Overflow::EvictOldest)
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [1, 2, 3, 4, 1]);
+    }
+
+    #[test]
+    fn fixed_passes_through() {
+        let og = [1, 2, 3, 1, 4, 2, 4];
+        let dp = og
+            .into_iter()
+            .dedup_non_con_fixed::<3>(Overflow::PassThrough)
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [1, 2, 3, 4, 4]);
+    }
+
+    #[test]
+    fn fixed_by_key_with_room_agrees_with_vec() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og
+            .chars()
+            .dedup_non_con_fixed_by_key::<_, _, 32>(Overflow::PassThrough, |c| {
+                c.to_ascii_uppercase()
+            })
+            .collect::<String>();
+        assert_eq!(&dp, "spor dwjnychlite");
+    }
+
+    #[test]
+    fn fixed_checked_reports_overflow() {
+        let og = [1, 2, 1, 3, 2, 3];
+        let dp = og
+            .into_iter()
+            .dedup_non_con_fixed_checked::<2>()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dp,
+            [Ok(1), Ok(2), Err(CapacityError(3)), Err(CapacityError(3))]
+        );
+    }
+
+    #[test]
+    fn fixed_with_no_room_passes_everything() {
+        let og = [1, 1, 1];
+        let dp = og
+            .into_iter()
+            .dedup_non_con_fixed::<0>(Overflow::EvictOldest)
+            .count();
+        assert_eq!(dp, 3);
+    }
 }
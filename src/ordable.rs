@@ -1,46 +1,40 @@
-use std::collections::BTreeSet;
+use alloc::{collections::BTreeSet, vec::Vec};
+use core::cmp::Ordering;
 
-/* # dedup */
+use crate::{
+    hasher,
+    store::{
+        self, DedupStore, DedupStoreAdapter, DedupStoreBy, DedupStoreByAdapter, DedupStoreByKey,
+        DedupStoreByKeyAdapter, SeenStore, TryDedupStore, TryDedupStoreAdapter, TryDedupStoreByKey,
+        TryDedupStoreByKeyAdapter,
+    },
+};
 
-/// removes consecutive equal elements
-#[derive(Debug, Clone)]
-pub struct DedupOrd<I>
-where
-    I: Iterator,
-{
-    iterator: I,
-    seen: BTreeSet<I::Item>,
-}
+/* # deduplicator */
 
-impl<I> Iterator for DedupOrd<I>
-where
-    I: Iterator,
-    I::Item: Ord + Clone,
-{
-    type Item = I::Item;
-
-    #[allow(clippy::while_let_on_iterator, reason = "seems more readable here")]
-    fn next(&mut self) -> Option<I::Item> {
-        while let Some(item) = self.iterator.next() {
-            if !self.seen.contains(&item) {
-                self.seen.insert(item.clone());
-                return Some(item);
-            }
-        }
-        None
-    }
-}
+/// removes equal elements pushed one at a time, remembering them in a `BTreeSet`
+pub type Deduplicator<T> = store::Deduplicator<T, BTreeSet<T>>;
+
+/* # dedup */
+
+/// removes equal elements, remembering them in a `BTreeSet`
+pub type DedupOrd<I> = DedupStore<I, BTreeSet<<I as Iterator>::Item>>;
 
-/// provides the `dedup` method on `Iterator`s
+/// provides the `dedup_ord` method on `Iterator`s
 pub trait DedupOrdAdapter: Iterator {
     fn dedup_ord(self) -> DedupOrd<Self>
     where
         Self: Sized,
     {
-        DedupOrd {
-            seen: BTreeSet::new(),
-            iterator: self,
-        }
+        self.dedup_store(BTreeSet::new())
+    }
+
+    /// as `dedup_ord`, but treats the elements of `seen` as already yielded
+    fn dedup_ord_seeded(self, seen: BTreeSet<Self::Item>) -> DedupOrd<Self>
+    where
+        Self: Sized,
+    {
+        self.dedup_store(seen)
     }
 }
 
@@ -48,48 +42,29 @@ impl<I> DedupOrdAdapter for I where I: Iterator {}
 
 /* # dedup by */
 
-/// removes consecutive elements, whose equality is asserted by provided function
-#[derive(Debug, Clone)]
-pub struct DedupOrdBy<I, F>
-where
-    I: Iterator,
-{
-    iterator: I,
-    seen: BTreeSet<I::Item>,
-    equivalence: F,
-}
-
-impl<I, F> Iterator for DedupOrdBy<I, F>
-where
-    I: Iterator,
-    I::Item: Ord + Clone,
-    F: Fn(&I::Item, &I::Item) -> bool,
-{
-    type Item = I::Item;
-
-    fn next(&mut self) -> Option<I::Item> {
-        while let Some(item) = self.iterator.next() {
-            if !self.seen.iter().any(|old| (self.equivalence)(old, &item)) {
-                self.seen.insert(item.clone());
-                return Some(item);
-            }
-        }
-        None
-    }
-}
+/// removes elements, whose equality is asserted by provided function, remembering them in a `BTreeSet`
+///
+/// the function cannot be used for ordering, so every element is compared with all remembered ones;
+/// prefer `dedup_ord_by_cmp` when a comparator is available
+pub type DedupOrdBy<I, F> = DedupStoreBy<I, BTreeSet<<I as Iterator>::Item>, F>;
 
-/// provides the `dedup_by` method on `Iterator`s
+/// provides the `dedup_ord_by` method on `Iterator`s
 pub trait DedupOrdByAdapter<F>: Iterator {
     fn dedup_ord_by(self, equivalence: F) -> DedupOrdBy<Self, F>
     where
         Self: Sized,
         F: Fn(&Self::Item, &Self::Item) -> bool,
     {
-        DedupOrdBy {
-            seen: BTreeSet::new(),
-            iterator: self,
-            equivalence,
-        }
+        self.dedup_store_by(BTreeSet::new(), equivalence)
+    }
+
+    /// as `dedup_ord_by`, but treats the elements of `seen` as already yielded
+    fn dedup_ord_by_seeded(self, seen: BTreeSet<Self::Item>, equivalence: F) -> DedupOrdBy<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item, &Self::Item) -> bool,
+    {
+        self.dedup_store_by(seen, equivalence)
     }
 }
 
@@ -97,55 +72,217 @@ impl<I, F> DedupOrdByAdapter<F> for I where I: Iterator {}
 
 /* # dedup by key */
 
-/// removes consecutive elements, which give equal outputs from provided function
+/// removes elements, which give equal outputs from provided function, remembering the outputs in a `BTreeSet`
+pub type DedupOrdByKey<I, F, K> = DedupStoreByKey<I, BTreeSet<K>, F, K>;
+
+/// provides the `dedup_ord_by_key` method on `Iterator`s
+pub trait DedupOrdByKeyAdapter<F, K>: Iterator {
+    fn dedup_ord_by_key(self, function: F) -> DedupOrdByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(BTreeSet::new(), function)
+    }
+
+    /// as `dedup_ord_by_key`, but treats the outputs in `seen` as already yielded
+    fn dedup_ord_by_key_seeded(self, seen: BTreeSet<K>, function: F) -> DedupOrdByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(seen, function)
+    }
+}
+
+impl<I, F, K> DedupOrdByKeyAdapter<F, K> for I where I: Iterator {}
+
+/* # try dedup */
+
+/// removes equal `Ok` elements, remembering them in a `BTreeSet`, and passes errors through
+pub type TryDedupOrd<I, T> = TryDedupStore<I, BTreeSet<T>>;
+
+/// provides the `try_dedup_ord` method on `Iterator`s of `Result`s
+pub trait TryDedupOrdAdapter<T, E>: Iterator<Item = Result<T, E>> {
+    fn try_dedup_ord(self) -> TryDedupOrd<Self, T>
+    where
+        Self: Sized,
+    {
+        self.try_dedup_store(BTreeSet::new())
+    }
+}
+
+impl<I, T, E> TryDedupOrdAdapter<T, E> for I where I: Iterator<Item = Result<T, E>> {}
+
+/* # try dedup by key */
+
+/// removes `Ok` elements, which give equal outputs from provided fallible function,
+/// remembering the outputs in a `BTreeSet`, and passes errors through
+pub type TryDedupOrdByKey<I, F, K> = TryDedupStoreByKey<I, BTreeSet<K>, F>;
+
+/// provides the `try_dedup_ord_by_key` method on `Iterator`s of `Result`s
+pub trait TryDedupOrdByKeyAdapter<T, E, F, K>: Iterator<Item = Result<T, E>> {
+    fn try_dedup_ord_by_key(self, function: F) -> TryDedupOrdByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&T) -> Result<K, E>,
+    {
+        self.try_dedup_store_by_key(BTreeSet::new(), function)
+    }
+}
+
+impl<I, T, E, F, K> TryDedupOrdByKeyAdapter<T, E, F, K> for I where I: Iterator<Item = Result<T, E>> {}
+
+/* # comparator set */
+
+/// marks a missing child
+const NIL: usize = usize::MAX;
+
 #[derive(Debug, Clone)]
-pub struct DedupOrdByKey<I, F, K>
+struct Node<T> {
+    item: T,
+    priority: u64,
+    left: usize,
+    right: usize,
+}
+
+/// an ordered set, whose elements are arranged by provided comparator
+///
+/// kept as a treap over an arena of nodes, so lookups and insertions take expected logarithmic time
+#[derive(Debug, Clone)]
+pub struct ComparatorSet<T, F> {
+    nodes: Vec<Node<T>>,
+    root: usize,
+    comparator: F,
+}
+
+impl<T, F> ComparatorSet<T, F> {
+    pub fn new(comparator: F) -> Self {
+        Self {
+            nodes: Vec::new(),
+            root: NIL,
+            comparator,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.root = NIL;
+    }
+}
+
+impl<T, F> ComparatorSet<T, F>
 where
-    I: Iterator,
-    F: Fn(&I::Item) -> K,
+    F: Fn(&T, &T) -> Ordering,
 {
-    iterator: I,
-    seen: BTreeSet<K>,
-    function: F,
+    fn find(&self, item: &T) -> Option<usize> {
+        let mut node = self.root;
+        while node != NIL {
+            node = match (self.comparator)(item, &self.nodes[node].item) {
+                Ordering::Less => self.nodes[node].left,
+                Ordering::Greater => self.nodes[node].right,
+                Ordering::Equal => return Some(node),
+            };
+        }
+        None
+    }
+
+    /// links the node at `index` into the subtree rooted at `node`, returning the new root of the subtree
+    fn link(&mut self, node: usize, index: usize) -> usize {
+        if node == NIL {
+            return index;
+        }
+        match (self.comparator)(&self.nodes[index].item, &self.nodes[node].item) {
+            Ordering::Greater => {
+                let right = self.link(self.nodes[node].right, index);
+                self.nodes[node].right = right;
+                match self.nodes[right].priority > self.nodes[node].priority {
+                    true => {
+                        self.nodes[node].right = self.nodes[right].left;
+                        self.nodes[right].left = node;
+                        right
+                    }
+                    false => node,
+                }
+            }
+            _ => {
+                let left = self.link(self.nodes[node].left, index);
+                self.nodes[node].left = left;
+                match self.nodes[left].priority > self.nodes[node].priority {
+                    true => {
+                        self.nodes[node].left = self.nodes[left].right;
+                        self.nodes[left].right = node;
+                        left
+                    }
+                    false => node,
+                }
+            }
+        }
+    }
+}
+
+/// scrambles the arena index into a node priority
+fn priority(index: usize) -> u64 {
+    hasher::splitmix(index as u64)
 }
 
-impl<I, F, K> Iterator for DedupOrdByKey<I, F, K>
+impl<T, F> SeenStore<T> for ComparatorSet<T, F>
 where
-    I: Iterator,
-    F: Fn(&I::Item) -> K,
-    K: Ord,
+    F: Fn(&T, &T) -> Ordering,
 {
-    type Item = I::Item;
-
-    #[allow(clippy::while_let_on_iterator, reason = "seems more readable here")]
-    fn next(&mut self) -> Option<I::Item> {
-        while let Some(item) = self.iterator.next() {
-            let key = (self.function)(&item);
-            if !self.seen.contains(&key) {
-                self.seen.insert(key);
-                return Some(item);
-            }
+    fn contains(&self, item: &T) -> bool {
+        self.find(item).is_some()
+    }
+
+    fn insert(&mut self, item: T) -> bool {
+        if self.find(&item).is_some() {
+            return false;
         }
-        None
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            item,
+            priority: priority(index),
+            left: NIL,
+            right: NIL,
+        });
+        self.root = self.link(self.root, index);
+        true
+    }
+
+    fn len(&self) -> usize {
+        ComparatorSet::len(self)
+    }
+
+    fn clear(&mut self) {
+        ComparatorSet::clear(self);
     }
 }
 
-/// Provides the `dedup_by_key` method on `Iterator`s.
-pub trait DedupOrdByKeyAdapter<F, K>: Iterator {
-    fn dedup_ord_by_key(self, function: F) -> DedupOrdByKey<Self, F, K>
+/* # dedup by comparator */
+
+/// removes elements, which compare as equal under provided comparator, remembering them in a `ComparatorSet`
+pub type DedupOrdByCmp<I, F> = DedupStore<I, ComparatorSet<<I as Iterator>::Item, F>>;
+
+/// provides the `dedup_ord_by_cmp` method on `Iterator`s
+pub trait DedupOrdByCmpAdapter<F>: Iterator {
+    fn dedup_ord_by_cmp(self, comparator: F) -> DedupOrdByCmp<Self, F>
     where
         Self: Sized,
-        F: Fn(&Self::Item) -> K,
+        F: Fn(&Self::Item, &Self::Item) -> Ordering,
     {
-        DedupOrdByKey {
-            seen: BTreeSet::new(),
-            iterator: self,
-            function,
-        }
+        self.dedup_store(ComparatorSet::new(comparator))
     }
 }
 
-impl<I, F, K> DedupOrdByKeyAdapter<F, K> for I where I: Iterator {}
+impl<I, F> DedupOrdByCmpAdapter<F> for I where I: Iterator {}
 
 #[cfg(test)]
 mod tests {
@@ -233,4 +370,83 @@ mod tests {
         let v = t.chars().dedup_ord_by_key(|_| 0_u8).collect::<String>();
         assert_eq!(&v, "a");
     }
+
+    #[test]
+    fn deduplicate_by_comparator() {
+        let og = [1.5_f64, -0.0, 2.5, 1.5, 0.0, 2.5, -1.0];
+        let dp = og
+            .into_iter()
+            .dedup_ord_by_cmp(|a: &f64, b: &f64| a.total_cmp(b))
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [1.5, -0.0, 2.5, 0.0, -1.0]);
+    }
+
+    #[test]
+    fn deduplicate_by_reversed_comparator() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og
+            .chars()
+            .dedup_ord_by_cmp(|a: &char, b: &char| b.cmp(a))
+            .collect::<String>();
+        assert_eq!(&dp, "spor dwjnychlite");
+    }
+
+    #[test]
+    fn comparator_set_handles_sorted_input() {
+        let mut set = ComparatorSet::new(|a: &u32, b: &u32| a.cmp(b));
+        (0..1 << 16).for_each(|n| {
+            set.insert(n);
+        });
+        assert_eq!(set.len(), 1 << 16);
+        assert!((0..1 << 16).all(|n| set.contains(&n)));
+        assert!(!set.contains(&(1 << 16)));
+    }
+
+    #[test]
+    fn deduplicator_agrees_with_adapter() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let mut dd = Deduplicator::new();
+        let dp = og.chars().filter(|&c| dd.insert(c)).collect::<String>();
+        assert_eq!(dp, og.chars().dedup_ord().collect::<String>());
+        assert_eq!(dd.len(), dp.chars().count());
+    }
+
+    #[test]
+    fn dedup_by_key_seeded() {
+        let og = [(1, 'a'), (2, 'b'), (3, 'c'), (2, 'd')];
+        let mut dp = og
+            .into_iter()
+            .dedup_ord_by_key_seeded(BTreeSet::from([1]), |pair| pair.0);
+        assert_eq!(dp.by_ref().collect::<Vec<_>>(), [(2, 'b'), (3, 'c')]);
+        assert_eq!(dp.into_seen().len(), 3);
+    }
+
+    #[test]
+    fn try_dedup_ord_passes_errors_through() {
+        let og = ["3", "1", "x", "3", "2", "1"].map(|word| word.parse::<u8>());
+        let dp = og.clone().into_iter().try_dedup_ord().collect::<Vec<_>>();
+        assert_eq!(dp.len(), 4);
+        assert!(dp[2].is_err());
+        let dp = og
+            .into_iter()
+            .try_dedup_ord()
+            .filter_map(Result::ok)
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [3, 1, 2]);
+    }
+
+    #[test]
+    fn try_dedup_ord_by_key_short_circuits() {
+        let og = ["raz", "dwa", "trzy", "cztery", "pięć"];
+        let dp = og
+            .into_iter()
+            .map(Ok)
+            .try_dedup_ord_by_key(|word: &&str| match word.len() {
+                6 => Err(*word),
+                len => Ok(len),
+            })
+            .short_circuit()
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [Ok("raz"), Ok("trzy"), Err("cztery")]);
+    }
 }
@@ -0,0 +1,349 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+use crate::{
+    hashable::{ByKey, Equality, EquivalenceSet, HashEquivalence},
+    store::SeenStore,
+};
+
+/* # indexed equivalence */
+
+/// relates positions in a slice through the elements they point at
+#[derive(Debug, Clone, Copy)]
+pub struct Indexed<'a, T, E> {
+    slice: &'a [T],
+    equivalence: E,
+}
+
+impl<T, E> HashEquivalence<usize> for Indexed<'_, T, E>
+where
+    E: HashEquivalence<T>,
+{
+    fn hash<H>(&self, index: &usize, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.equivalence.hash(&self.slice[*index], state);
+    }
+
+    fn equivalent(&self, a: &usize, b: &usize) -> bool {
+        self.equivalence
+            .equivalent(&self.slice[*a], &self.slice[*b])
+    }
+}
+
+/* # unique indices */
+
+/// yields positions of first occurrences in a slice, remembering only the positions
+///
+/// elements are hashed and compared in place, so they are neither cloned nor keyed upfront
+#[derive(Debug, Clone)]
+pub struct UniqueIndices<'a, T, E, S = RandomState> {
+    slice: &'a [T],
+    position: usize,
+    seen: EquivalenceSet<usize, Indexed<'a, T, E>, S>,
+}
+
+impl<'a, T, E, S> UniqueIndices<'a, T, E, S> {
+    fn with_hasher(slice: &'a [T], equivalence: E, hash_builder: S) -> Self {
+        Self {
+            slice,
+            position: 0,
+            seen: EquivalenceSet::with_hasher(Indexed { slice, equivalence }, hash_builder),
+        }
+    }
+
+    /// the positions yielded so far
+    pub fn seen(&self) -> &[usize] {
+        self.seen.iter().as_slice()
+    }
+}
+
+impl<T, E, S> Iterator for UniqueIndices<'_, T, E, S>
+where
+    E: HashEquivalence<T>,
+    S: BuildHasher,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.position < self.slice.len() {
+            let index = self.position;
+            self.position += 1;
+            if self.seen.insert(index) {
+                return Some(index);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.slice.len() - self.position;
+        (
+            usize::from(remaining > 0 && self.seen.is_empty()),
+            Some(remaining),
+        )
+    }
+}
+
+/* # dedup slice */
+
+/// yields references to first occurrences in a slice, remembering only their positions
+#[derive(Debug, Clone)]
+pub struct DedupSlice<'a, T, E, S = RandomState> {
+    indices: UniqueIndices<'a, T, E, S>,
+}
+
+impl<'a, T, E, S> DedupSlice<'a, T, E, S> {
+    /// gives back the positions of the yielded elements
+    pub fn into_indices(self) -> UniqueIndices<'a, T, E, S> {
+        self.indices
+    }
+}
+
+impl<'a, T, E, S> Iterator for DedupSlice<'a, T, E, S>
+where
+    E: HashEquivalence<T>,
+    S: BuildHasher,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let slice = self.indices.slice;
+        self.indices.next().map(|index| &slice[index])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+/// provides the `unique_indices` and `dedup_slice` methods on slices
+pub trait DedupSliceAdapter<T> {
+    fn unique_indices(&self) -> UniqueIndices<'_, T, Equality>;
+
+    /// as `unique_indices`, but hashes the elements with provided `BuildHasher`
+    fn unique_indices_with_hasher<S>(&self, hash_builder: S) -> UniqueIndices<'_, T, Equality, S>;
+
+    fn unique_indices_by_key<F, K>(&self, function: F) -> UniqueIndices<'_, T, ByKey<F>>
+    where
+        F: Fn(&T) -> K;
+
+    /// as `unique_indices_by_key`, but hashes the outputs with provided `BuildHasher`
+    fn unique_indices_by_key_with_hasher<F, K, S>(
+        &self,
+        function: F,
+        hash_builder: S,
+    ) -> UniqueIndices<'_, T, ByKey<F>, S>
+    where
+        F: Fn(&T) -> K;
+
+    fn unique_indices_by_equivalence<E>(&self, equivalence: E) -> UniqueIndices<'_, T, E>;
+
+    /// as `unique_indices_by_equivalence`, but hashes with provided `BuildHasher`
+    fn unique_indices_by_equivalence_with_hasher<E, S>(
+        &self,
+        equivalence: E,
+        hash_builder: S,
+    ) -> UniqueIndices<'_, T, E, S>;
+
+    fn dedup_slice(&self) -> DedupSlice<'_, T, Equality>;
+
+    /// as `dedup_slice`, but hashes the elements with provided `BuildHasher`
+    fn dedup_slice_with_hasher<S>(&self, hash_builder: S) -> DedupSlice<'_, T, Equality, S>;
+
+    fn dedup_slice_by_key<F, K>(&self, function: F) -> DedupSlice<'_, T, ByKey<F>>
+    where
+        F: Fn(&T) -> K;
+
+    /// as `dedup_slice_by_key`, but hashes the outputs with provided `BuildHasher`
+    fn dedup_slice_by_key_with_hasher<F, K, S>(
+        &self,
+        function: F,
+        hash_builder: S,
+    ) -> DedupSlice<'_, T, ByKey<F>, S>
+    where
+        F: Fn(&T) -> K;
+
+    fn dedup_slice_by_equivalence<E>(&self, equivalence: E) -> DedupSlice<'_, T, E>;
+
+    /// as `dedup_slice_by_equivalence`, but hashes with provided `BuildHasher`
+    fn dedup_slice_by_equivalence_with_hasher<E, S>(
+        &self,
+        equivalence: E,
+        hash_builder: S,
+    ) -> DedupSlice<'_, T, E, S>;
+}
+
+impl<T> DedupSliceAdapter<T> for [T] {
+    fn unique_indices(&self) -> UniqueIndices<'_, T, Equality> {
+        self.unique_indices_with_hasher(RandomState::new())
+    }
+
+    fn unique_indices_with_hasher<S>(&self, hash_builder: S) -> UniqueIndices<'_, T, Equality, S> {
+        self.unique_indices_by_equivalence_with_hasher(Equality, hash_builder)
+    }
+
+    fn unique_indices_by_key<F, K>(&self, function: F) -> UniqueIndices<'_, T, ByKey<F>>
+    where
+        F: Fn(&T) -> K,
+    {
+        self.unique_indices_by_key_with_hasher(function, RandomState::new())
+    }
+
+    fn unique_indices_by_key_with_hasher<F, K, S>(
+        &self,
+        function: F,
+        hash_builder: S,
+    ) -> UniqueIndices<'_, T, ByKey<F>, S>
+    where
+        F: Fn(&T) -> K,
+    {
+        self.unique_indices_by_equivalence_with_hasher(ByKey(function), hash_builder)
+    }
+
+    fn unique_indices_by_equivalence<E>(&self, equivalence: E) -> UniqueIndices<'_, T, E> {
+        self.unique_indices_by_equivalence_with_hasher(equivalence, RandomState::new())
+    }
+
+    fn unique_indices_by_equivalence_with_hasher<E, S>(
+        &self,
+        equivalence: E,
+        hash_builder: S,
+    ) -> UniqueIndices<'_, T, E, S> {
+        UniqueIndices::with_hasher(self, equivalence, hash_builder)
+    }
+
+    fn dedup_slice(&self) -> DedupSlice<'_, T, Equality> {
+        self.dedup_slice_with_hasher(RandomState::new())
+    }
+
+    fn dedup_slice_with_hasher<S>(&self, hash_builder: S) -> DedupSlice<'_, T, Equality, S> {
+        DedupSlice {
+            indices: self.unique_indices_with_hasher(hash_builder),
+        }
+    }
+
+    fn dedup_slice_by_key<F, K>(&self, function: F) -> DedupSlice<'_, T, ByKey<F>>
+    where
+        F: Fn(&T) -> K,
+    {
+        self.dedup_slice_by_key_with_hasher(function, RandomState::new())
+    }
+
+    fn dedup_slice_by_key_with_hasher<F, K, S>(
+        &self,
+        function: F,
+        hash_builder: S,
+    ) -> DedupSlice<'_, T, ByKey<F>, S>
+    where
+        F: Fn(&T) -> K,
+    {
+        DedupSlice {
+            indices: self.unique_indices_by_key_with_hasher(function, hash_builder),
+        }
+    }
+
+    fn dedup_slice_by_equivalence<E>(&self, equivalence: E) -> DedupSlice<'_, T, E> {
+        self.dedup_slice_by_equivalence_with_hasher(equivalence, RandomState::new())
+    }
+
+    fn dedup_slice_by_equivalence_with_hasher<E, S>(
+        &self,
+        equivalence: E,
+        hash_builder: S,
+    ) -> DedupSlice<'_, T, E, S> {
+        DedupSlice {
+            indices: self.unique_indices_by_equivalence_with_hasher(equivalence, hash_builder),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hasher::FastState;
+
+    #[test]
+    fn deduplicate_empty_slice() {
+        let og = Vec::<u8>::new();
+        assert_eq!(og.unique_indices().count(), 0);
+        assert_eq!(og.dedup_slice().count(), 0);
+    }
+
+    #[test]
+    fn remove_duplicate_number() {
+        let og: [i32; 7] = [10, 20, 20, 21, 30, 30, 20];
+        let dp = og.dedup_slice().copied().collect::<Vec<_>>();
+        assert_eq!(dp, [10, 20, 21, 30]);
+        let ix = og.unique_indices().collect::<Vec<_>>();
+        assert_eq!(ix, [0, 1, 3, 4]);
+    }
+
+    #[test]
+    fn deduplicate_with_provided_hasher() {
+        let og = ["Raz", "dwa", "RAZ", "Trzy", "DWA", "trzy", "raz"];
+        let ix = og
+            .unique_indices_with_hasher(FastState::new())
+            .collect::<Vec<_>>();
+        assert_eq!(ix, [0, 1, 2, 3, 4, 5, 6]);
+        let dp = og
+            .dedup_slice_by_key_with_hasher(|word| word.to_lowercase(), FastState::with_seed(7))
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(dp, ["Raz", "dwa", "Trzy"]);
+    }
+
+    #[test]
+    fn deduplicate_without_clone() {
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct Test {
+            id: u8,
+            other: Vec<u8>,
+        }
+        let og = [
+            Test {
+                id: 0,
+                other: vec![0, 1, 2],
+            },
+            Test {
+                id: 0,
+                other: vec![0, 1, 2, 3],
+            },
+            Test {
+                id: 1,
+                other: vec![0, 1, 2],
+            },
+        ];
+        let dp = og.dedup_slice_by_key(|test| test.id).collect::<Vec<_>>();
+        assert!(std::ptr::eq(dp[0], &og[0]));
+        assert!(std::ptr::eq(dp[1], &og[2]));
+        assert_eq!(og.unique_indices().collect::<Vec<_>>(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn deduplicate_by_equivalence() {
+        let og = ["Raz", "dwa", "RAZ", "Trzy", "DWA", "trzy", "raz"];
+        let ix = og
+            .unique_indices_by_equivalence((
+                |word: &&str| word.to_lowercase(),
+                |a: &&str, b: &&str| a.eq_ignore_ascii_case(b),
+            ))
+            .collect::<Vec<_>>();
+        assert_eq!(ix, [0, 1, 3]);
+    }
+
+    #[test]
+    fn seen_lists_yielded_positions() {
+        let og = "ssporrro podwojjonnyych litterr"
+            .chars()
+            .collect::<Vec<_>>();
+        let mut ix = og.unique_indices();
+        let yielded = ix.by_ref().collect::<Vec<_>>();
+        assert_eq!(ix.seen(), yielded.as_slice());
+        let dp = yielded.iter().map(|&index| og[index]).collect::<String>();
+        assert_eq!(&dp, "spor dwjnychlite");
+    }
+}
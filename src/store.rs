@@ -0,0 +1,873 @@
+use alloc::{collections::BTreeSet, vec::Vec};
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::{
+    collections::HashSet,
+    hash::{BuildHasher, Hash},
+};
+
+/* # seen store */
+
+/// remembers which elements have already been yielded
+pub trait SeenStore<T> {
+    /// checks whether an element equal to `item` has already been recorded
+    fn contains(&self, item: &T) -> bool;
+
+    /// checks whether an element equal to `item` has already been recorded, as the adapters do for every element,
+    /// letting stores which track recency note that it was seen again
+    fn observe(&mut self, item: &T) -> bool {
+        self.contains(item)
+    }
+
+    /// records `item`, returning whether it was not recorded before
+    fn insert(&mut self, item: T) -> bool;
+
+    /// records `item`, unless an equal element is observed, returning whether it is new, as the adapters do
+    /// for every element
+    ///
+    /// stores, which can look the element up and record it at once, override it to do so
+    fn record(&mut self, item: T) -> bool {
+        if self.observe(&item) {
+            return false;
+        }
+        self.insert(item);
+        true
+    }
+
+    /// as `record`, but with a borrowed `item`, which is cloned only when a new one is recorded
+    ///
+    /// stores, which keep only hashes of their elements, override it to record `item` without cloning it,
+    /// and implement `HashedStore`, so that adapters need no `Clone` at all
+    fn record_ref(&mut self, item: &T) -> bool
+    where
+        T: Clone,
+    {
+        if self.observe(item) {
+            return false;
+        }
+        self.insert(item.clone());
+        true
+    }
+
+    /// the number of recorded elements
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// forgets all recorded elements
+    fn clear(&mut self);
+}
+
+/// a store, which can also forget single elements, e.g. ones which may legitimately come again
+pub trait RemoveStore<T>: SeenStore<T> {
+    /// forgets an element equal to `item`, returning whether one was recorded
+    fn remove(&mut self, item: &T) -> bool;
+}
+
+/// a store, which keeps only hashes of its elements, so that it can record them borrowed, without `Clone`
+pub trait HashedStore<T>: SeenStore<T> {
+    /// as `record`, but with a borrowed `item`, from which only a hash is taken
+    fn record_hashed(&mut self, item: &T) -> bool;
+}
+
+/// remembers yielded elements, so that they can be scanned with an arbitrary predicate
+pub trait ScanStore<T> {
+    /// checks whether any recorded element satisfies `predicate`
+    fn any<P>(&self, predicate: P) -> bool
+    where
+        P: FnMut(&T) -> bool;
+
+    /// records `item`, without checking whether it was recorded before
+    fn push(&mut self, item: T);
+}
+
+#[cfg(feature = "std")]
+impl<T, S> SeenStore<T> for HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    fn contains(&self, item: &T) -> bool {
+        HashSet::contains(self, item)
+    }
+
+    fn insert(&mut self, item: T) -> bool {
+        HashSet::insert(self, item)
+    }
+
+    fn record(&mut self, item: T) -> bool {
+        HashSet::insert(self, item)
+    }
+
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+
+    fn clear(&mut self) {
+        HashSet::clear(self);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, S> RemoveStore<T> for HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    fn remove(&mut self, item: &T) -> bool {
+        HashSet::remove(self, item)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, S> ScanStore<T> for HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    fn any<P>(&self, predicate: P) -> bool
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter().any(predicate)
+    }
+
+    fn push(&mut self, item: T) {
+        self.insert(item);
+    }
+}
+
+impl<T> SeenStore<T> for BTreeSet<T>
+where
+    T: Ord,
+{
+    fn contains(&self, item: &T) -> bool {
+        BTreeSet::contains(self, item)
+    }
+
+    fn insert(&mut self, item: T) -> bool {
+        BTreeSet::insert(self, item)
+    }
+
+    fn record(&mut self, item: T) -> bool {
+        BTreeSet::insert(self, item)
+    }
+
+    fn len(&self) -> usize {
+        BTreeSet::len(self)
+    }
+
+    fn clear(&mut self) {
+        BTreeSet::clear(self);
+    }
+}
+
+impl<T> RemoveStore<T> for BTreeSet<T>
+where
+    T: Ord,
+{
+    fn remove(&mut self, item: &T) -> bool {
+        BTreeSet::remove(self, item)
+    }
+}
+
+impl<T> ScanStore<T> for BTreeSet<T>
+where
+    T: Ord,
+{
+    fn any<P>(&self, predicate: P) -> bool
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter().any(predicate)
+    }
+
+    fn push(&mut self, item: T) {
+        self.insert(item);
+    }
+}
+
+impl<T> SeenStore<T> for Vec<T>
+where
+    T: PartialEq,
+{
+    fn contains(&self, item: &T) -> bool {
+        self.as_slice().contains(item)
+    }
+
+    fn insert(&mut self, item: T) -> bool {
+        match self.as_slice().contains(&item) {
+            true => false,
+            false => {
+                self.push(item);
+                true
+            }
+        }
+    }
+
+    fn record(&mut self, item: T) -> bool {
+        SeenStore::insert(self, item)
+    }
+
+    /// scans the elements once, cloning `item` only if none is equal
+    fn record_ref(&mut self, item: &T) -> bool
+    where
+        T: Clone,
+    {
+        match self.as_slice().contains(item) {
+            true => false,
+            false => {
+                self.push(item.clone());
+                true
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn clear(&mut self) {
+        Vec::clear(self);
+    }
+}
+
+impl<T> RemoveStore<T> for Vec<T>
+where
+    T: PartialEq,
+{
+    fn remove(&mut self, item: &T) -> bool {
+        match self.iter().position(|old| old == item) {
+            Some(position) => {
+                Vec::remove(self, position);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<T> ScanStore<T> for Vec<T> {
+    fn any<P>(&self, predicate: P) -> bool
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter().any(predicate)
+    }
+
+    fn push(&mut self, item: T) {
+        Vec::push(self, item);
+    }
+}
+
+/* # dedup */
+
+/// removes equal elements, remembering them in the provided store
+#[derive(Debug, Clone)]
+pub struct DedupStore<I, S> {
+    iterator: I,
+    seen: S,
+}
+
+impl<I, S> DedupStore<I, S>
+where
+    I: Iterator,
+{
+    /// the store of elements seen so far
+    pub fn seen(&self) -> &S {
+        &self.seen
+    }
+
+    /// stops iterating, giving back the store of elements seen so far
+    pub fn into_seen(self) -> S {
+        self.seen
+    }
+}
+
+impl<I, S> Iterator for DedupStore<I, S>
+where
+    I: Iterator,
+    I::Item: Clone,
+    S: SeenStore<I::Item>,
+{
+    type Item = I::Item;
+
+    #[allow(clippy::while_let_on_iterator, reason = "seems more readable here")]
+    fn next(&mut self) -> Option<I::Item> {
+        while let Some(item) = self.iterator.next() {
+            if self.seen.record_ref(&item) {
+                return Some(item);
+            }
+        }
+        None
+    }
+}
+
+/// provides the `dedup_store` method on `Iterator`s
+pub trait DedupStoreAdapter<S>: Iterator {
+    fn dedup_store(self, seen: S) -> DedupStore<Self, S>
+    where
+        Self: Sized,
+    {
+        DedupStore {
+            iterator: self,
+            seen,
+        }
+    }
+}
+
+impl<I, S> DedupStoreAdapter<S> for I where I: Iterator {}
+
+/* # dedup hashed */
+
+/// removes equal elements, remembering only their hashes in the provided store, so elements need not be `Clone`
+#[derive(Debug, Clone)]
+pub struct DedupHashedStore<I, S> {
+    iterator: I,
+    seen: S,
+}
+
+impl<I, S> DedupHashedStore<I, S>
+where
+    I: Iterator,
+{
+    /// the store of hashes seen so far
+    pub fn seen(&self) -> &S {
+        &self.seen
+    }
+
+    /// stops iterating, giving back the store of hashes seen so far
+    pub fn into_seen(self) -> S {
+        self.seen
+    }
+}
+
+impl<I, S> Iterator for DedupHashedStore<I, S>
+where
+    I: Iterator,
+    S: HashedStore<I::Item>,
+{
+    type Item = I::Item;
+
+    #[allow(clippy::while_let_on_iterator, reason = "seems more readable here")]
+    fn next(&mut self) -> Option<I::Item> {
+        while let Some(item) = self.iterator.next() {
+            if self.seen.record_hashed(&item) {
+                return Some(item);
+            }
+        }
+        None
+    }
+}
+
+/// provides the `dedup_hashed_store` method on `Iterator`s
+pub trait DedupHashedStoreAdapter<S>: Iterator {
+    fn dedup_hashed_store(self, seen: S) -> DedupHashedStore<Self, S>
+    where
+        Self: Sized,
+    {
+        DedupHashedStore {
+            iterator: self,
+            seen,
+        }
+    }
+}
+
+impl<I, S> DedupHashedStoreAdapter<S> for I where I: Iterator {}
+
+/* # dedup by */
+
+/// removes elements, whose equality is asserted by provided function, remembering them in the provided store
+#[derive(Debug, Clone)]
+pub struct DedupStoreBy<I, S, F> {
+    iterator: I,
+    seen: S,
+    equivalence: F,
+}
+
+impl<I, S, F> DedupStoreBy<I, S, F>
+where
+    I: Iterator,
+{
+    /// the store of elements seen so far
+    pub fn seen(&self) -> &S {
+        &self.seen
+    }
+
+    /// stops iterating, giving back the store of elements seen so far
+    pub fn into_seen(self) -> S {
+        self.seen
+    }
+}
+
+impl<I, S, F> Iterator for DedupStoreBy<I, S, F>
+where
+    I: Iterator,
+    I::Item: Clone,
+    S: ScanStore<I::Item>,
+    F: Fn(&I::Item, &I::Item) -> bool,
+{
+    type Item = I::Item;
+
+    #[allow(clippy::while_let_on_iterator, reason = "seems more readable here")]
+    fn next(&mut self) -> Option<I::Item> {
+        while let Some(item) = self.iterator.next() {
+            if !self.seen.any(|old| (self.equivalence)(old, &item)) {
+                self.seen.push(item.clone());
+                return Some(item);
+            }
+        }
+        None
+    }
+}
+
+/// provides the `dedup_store_by` method on `Iterator`s
+pub trait DedupStoreByAdapter<S, F>: Iterator {
+    fn dedup_store_by(self, seen: S, equivalence: F) -> DedupStoreBy<Self, S, F>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item, &Self::Item) -> bool,
+    {
+        DedupStoreBy {
+            iterator: self,
+            seen,
+            equivalence,
+        }
+    }
+}
+
+impl<I, S, F> DedupStoreByAdapter<S, F> for I where I: Iterator {}
+
+/* # dedup by key */
+
+/// removes elements, which give equal outputs from provided function, remembering the outputs in the provided store
+#[derive(Debug, Clone)]
+pub struct DedupStoreByKey<I, S, F, K>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+{
+    iterator: I,
+    seen: S,
+    function: F,
+}
+
+impl<I, S, F, K> DedupStoreByKey<I, S, F, K>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+{
+    /// the store of elements seen so far
+    pub fn seen(&self) -> &S {
+        &self.seen
+    }
+
+    /// stops iterating, giving back the store of elements seen so far
+    pub fn into_seen(self) -> S {
+        self.seen
+    }
+}
+
+impl<I, S, F, K> Iterator for DedupStoreByKey<I, S, F, K>
+where
+    I: Iterator,
+    S: SeenStore<K>,
+    F: Fn(&I::Item) -> K,
+{
+    type Item = I::Item;
+
+    #[allow(clippy::while_let_on_iterator, reason = "seems more readable here")]
+    fn next(&mut self) -> Option<I::Item> {
+        while let Some(item) = self.iterator.next() {
+            if self.seen.record((self.function)(&item)) {
+                return Some(item);
+            }
+        }
+        None
+    }
+}
+
+/// provides the `dedup_store_by_key` method on `Iterator`s
+pub trait DedupStoreByKeyAdapter<S, F, K>: Iterator {
+    fn dedup_store_by_key(self, seen: S, function: F) -> DedupStoreByKey<Self, S, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        DedupStoreByKey {
+            iterator: self,
+            seen,
+            function,
+        }
+    }
+}
+
+impl<I, S, F, K> DedupStoreByKeyAdapter<S, F, K> for I where I: Iterator {}
+
+/* # try dedup */
+
+/// removes equal `Ok` elements, remembering them in the provided store, and passes errors through in order
+///
+/// errors are never remembered, see `short_circuit` for stopping at the first one
+#[derive(Debug, Clone)]
+pub struct TryDedupStore<I, S> {
+    iterator: I,
+    seen: S,
+    short_circuit: bool,
+    failed: bool,
+}
+
+impl<I, S> TryDedupStore<I, S> {
+    /// stops iterating after yielding the first error
+    pub fn short_circuit(self) -> Self {
+        Self {
+            short_circuit: true,
+            ..self
+        }
+    }
+
+    /// the store of elements seen so far
+    pub fn seen(&self) -> &S {
+        &self.seen
+    }
+
+    /// stops iterating, giving back the store of elements seen so far
+    pub fn into_seen(self) -> S {
+        self.seen
+    }
+}
+
+impl<I, S, T, E> Iterator for TryDedupStore<I, S>
+where
+    I: Iterator<Item = Result<T, E>>,
+    T: Clone,
+    S: SeenStore<T>,
+{
+    type Item = Result<T, E>;
+
+    fn next(&mut self) -> Option<Result<T, E>> {
+        if self.failed {
+            return None;
+        }
+        for item in self.iterator.by_ref() {
+            match item {
+                Ok(item) => {
+                    if self.seen.record_ref(&item) {
+                        return Some(Ok(item));
+                    }
+                }
+                Err(error) => {
+                    self.failed = self.short_circuit;
+                    return Some(Err(error));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// provides the `try_dedup_store` method on `Iterator`s of `Result`s
+pub trait TryDedupStoreAdapter<T, E, S>: Iterator<Item = Result<T, E>> {
+    fn try_dedup_store(self, seen: S) -> TryDedupStore<Self, S>
+    where
+        Self: Sized,
+    {
+        TryDedupStore {
+            iterator: self,
+            seen,
+            short_circuit: false,
+            failed: false,
+        }
+    }
+}
+
+impl<I, T, E, S> TryDedupStoreAdapter<T, E, S> for I where I: Iterator<Item = Result<T, E>> {}
+
+/* # try dedup by key */
+
+/// removes `Ok` elements, which give equal outputs from provided fallible function, remembering the outputs
+/// in the provided store, and passes errors of both the elements and the function through in order
+#[derive(Debug, Clone)]
+pub struct TryDedupStoreByKey<I, S, F> {
+    iterator: I,
+    seen: S,
+    function: F,
+    short_circuit: bool,
+    failed: bool,
+}
+
+impl<I, S, F> TryDedupStoreByKey<I, S, F> {
+    /// stops iterating after yielding the first error
+    pub fn short_circuit(self) -> Self {
+        Self {
+            short_circuit: true,
+            ..self
+        }
+    }
+
+    /// the store of outputs seen so far
+    pub fn seen(&self) -> &S {
+        &self.seen
+    }
+
+    /// stops iterating, giving back the store of outputs seen so far
+    pub fn into_seen(self) -> S {
+        self.seen
+    }
+}
+
+impl<I, S, F, T, E, K> Iterator for TryDedupStoreByKey<I, S, F>
+where
+    I: Iterator<Item = Result<T, E>>,
+    S: SeenStore<K>,
+    F: Fn(&T) -> Result<K, E>,
+{
+    type Item = Result<T, E>;
+
+    fn next(&mut self) -> Option<Result<T, E>> {
+        if self.failed {
+            return None;
+        }
+        for item in self.iterator.by_ref() {
+            match item.and_then(|item| Ok(((self.function)(&item)?, item))) {
+                Ok((key, item)) => {
+                    if self.seen.record(key) {
+                        return Some(Ok(item));
+                    }
+                }
+                Err(error) => {
+                    self.failed = self.short_circuit;
+                    return Some(Err(error));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// provides the `try_dedup_store_by_key` method on `Iterator`s of `Result`s
+pub trait TryDedupStoreByKeyAdapter<T, E, S, F, K>: Iterator<Item = Result<T, E>> {
+    fn try_dedup_store_by_key(self, seen: S, function: F) -> TryDedupStoreByKey<Self, S, F>
+    where
+        Self: Sized,
+        F: Fn(&T) -> Result<K, E>,
+    {
+        TryDedupStoreByKey {
+            iterator: self,
+            seen,
+            function,
+            short_circuit: false,
+            failed: false,
+        }
+    }
+}
+
+impl<I, T, E, S, F, K> TryDedupStoreByKeyAdapter<T, E, S, F, K> for I where
+    I: Iterator<Item = Result<T, E>>
+{
+}
+
+/* # deduplicator */
+
+/// removes equal elements pushed one at a time, remembering them in the provided store
+///
+/// offers the semantics of the `dedup_store` adapters to code, which receives elements outside of an `Iterator`
+#[derive(Debug, Clone)]
+pub struct Deduplicator<T, S> {
+    seen: S,
+    item: PhantomData<fn(T) -> bool>,
+}
+
+impl<T, S> Default for Deduplicator<T, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        Self::with_store(S::default())
+    }
+}
+
+impl<T, S> Deduplicator<T, S> {
+    pub fn new() -> Self
+    where
+        S: Default,
+    {
+        Self::default()
+    }
+
+    pub fn with_store(seen: S) -> Self {
+        Self {
+            seen,
+            item: PhantomData,
+        }
+    }
+}
+
+impl<T, S> Deduplicator<T, S>
+where
+    S: SeenStore<T>,
+{
+    /// records `item`, returning whether it should be emitted, i.e. whether it was not seen before
+    pub fn insert(&mut self, item: T) -> bool {
+        self.seen.record(item)
+    }
+
+    /// checks whether an element equal to `item` has already been seen
+    pub fn contains(&self, item: &T) -> bool {
+        self.seen.contains(item)
+    }
+
+    /// checks whether `item` would be emitted if it was inserted now, without recording it
+    pub fn would_emit(&self, item: &T) -> bool {
+        !self.seen.contains(item)
+    }
+
+    /// the number of distinct elements seen so far
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    /// forgets all seen elements
+    pub fn clear(&mut self) {
+        self.seen.clear();
+    }
+}
+
+impl<T, S> Deduplicator<T, S>
+where
+    S: RemoveStore<T>,
+{
+    /// forgets `item`, so that an equal element is emitted again, returning whether it was seen
+    pub fn remove(&mut self, item: &T) -> bool {
+        self.seen.remove(item)
+    }
+}
+
+impl<T, S> Deduplicator<T, S> {
+    /// the store of elements seen so far
+    pub fn seen(&self) -> &S {
+        &self.seen
+    }
+
+    /// gives back the store of elements seen so far
+    pub fn into_seen(self) -> S {
+        self.seen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a store remembering at most one element, to check that custom stores plug in
+    #[derive(Debug, Default)]
+    struct Latest<T>(Option<T>);
+
+    impl<T> SeenStore<T> for Latest<T>
+    where
+        T: PartialEq,
+    {
+        fn contains(&self, item: &T) -> bool {
+            self.0.as_ref() == Some(item)
+        }
+
+        fn insert(&mut self, item: T) -> bool {
+            let new = !SeenStore::contains(self, &item);
+            self.0 = Some(item);
+            new
+        }
+
+        fn len(&self) -> usize {
+            usize::from(self.0.is_some())
+        }
+
+        fn clear(&mut self) {
+            self.0 = None;
+        }
+    }
+
+    #[test]
+    fn deduplicate_empty_iterator() {
+        let og = Vec::<u8>::new();
+        let dp = og.iter().dedup_store(BTreeSet::new()).count();
+        assert_eq!(dp, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn remove_duplicate_character_in_every_store() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let hs = og.chars().dedup_store(HashSet::new()).collect::<String>();
+        let bs = og.chars().dedup_store(BTreeSet::new()).collect::<String>();
+        let vs = og.chars().dedup_store(Vec::new()).collect::<String>();
+        assert_eq!(&hs, "spor dwjnychlite");
+        assert_eq!(hs, bs);
+        assert_eq!(hs, vs);
+    }
+
+    #[test]
+    fn deduplicate_with_custom_store() {
+        let og: [i32; 7] = [10, 20, 20, 21, 30, 30, 20];
+        let dp = og
+            .into_iter()
+            .dedup_store(Latest::default())
+            .collect::<Vec<_>>();
+        assert_eq!(dp, [10, 20, 21, 30, 20]);
+    }
+
+    #[test]
+    fn remove_duplicate_whitespace() {
+        let og = "ttu    teżż  czasem   jakkaś litterka     dwa  rrazy";
+        let dp = og
+            .chars()
+            .dedup_store_by(Vec::new(), |a, b| a.is_whitespace() && b.is_whitespace())
+            .collect::<String>();
+        assert_eq!(&dp, "ttu teżżczasemjakkaślitterkadwarrazy");
+    }
+
+    #[test]
+    fn dedup_by_key() {
+        let og = ["raz", "dwa", "trzy", "cztery", "pięć"];
+        let dp = og
+            .into_iter()
+            .dedup_store_by_key(BTreeSet::new(), |word| word.chars().count())
+            .collect::<Vec<_>>();
+        assert_eq!(dp, ["raz", "trzy", "cztery"]);
+    }
+
+    #[test]
+    fn deduplicator_emits_first_occurrences() {
+        let og: [i32; 7] = [10, 20, 20, 21, 30, 30, 20];
+        let mut dd = Deduplicator::<_, Vec<_>>::new();
+        let dp = og.into_iter().filter(|&n| dd.insert(n)).collect::<Vec<_>>();
+        assert_eq!(dp, [10, 20, 21, 30]);
+        assert_eq!(dd.len(), 4);
+        assert!(dd.contains(&21));
+        assert!(!dd.would_emit(&21));
+        assert!(dd.would_emit(&40));
+        assert!(!dd.contains(&40));
+        assert!(dd.remove(&21));
+        assert!(!dd.remove(&21));
+        assert!(dd.insert(21));
+        dd.clear();
+        assert!(dd.is_empty());
+        assert!(dd.insert(20));
+    }
+
+    #[test]
+    fn continue_across_batches() {
+        let mut dp = [1, 2, 3, 2].into_iter().dedup_store(BTreeSet::from([3]));
+        assert_eq!(dp.by_ref().collect::<Vec<_>>(), [1, 2]);
+        let seen = dp.into_seen();
+        let mut dp = [4, 1, 5, 4].into_iter().dedup_store(seen);
+        assert_eq!(dp.seen(), &BTreeSet::from([1, 2, 3]));
+        assert_eq!(dp.by_ref().collect::<Vec<_>>(), [4, 5]);
+        assert_eq!(dp.into_seen(), BTreeSet::from([1, 2, 3, 4, 5]));
+    }
+}
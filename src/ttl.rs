@@ -0,0 +1,292 @@
+use std::{
+    cell::Cell,
+    collections::{hash_map::RandomState, HashMap, VecDeque},
+    hash::{BuildHasher, Hash},
+    time::{Duration, Instant},
+};
+
+use crate::store::{self, DedupStoreByKey, DedupStoreByKeyAdapter, SeenStore};
+
+/* # clock */
+
+/// a source of time, measured from an arbitrary but fixed starting point
+pub trait Clock {
+    /// the time elapsed since the starting point, which never decreases
+    fn now(&self) -> Duration;
+}
+
+/// measures time with `Instant`, from the moment it was created
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// a clock which stands still until it is moved on, e.g. in tests
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    now: Cell<Duration>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// moves the clock on by `duration`
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// a shared clock, e.g. one which is advanced while an adapter reads it
+impl<C> Clock for &C
+where
+    C: Clock + ?Sized,
+{
+    fn now(&self) -> Duration {
+        C::now(self)
+    }
+}
+
+/* # ttl store */
+
+/// remembers elements for `ttl` after they were recorded, after which equal elements are let through again
+///
+/// expired elements are reclaimed lazily, whenever the store is updated, so `len` may count some of them
+#[derive(Debug, Clone)]
+pub struct TtlStore<T, C = SystemClock, S = RandomState> {
+    ttl: Duration,
+    clock: C,
+    deadlines: HashMap<T, Duration, S>,
+    expiring: VecDeque<(Duration, T)>,
+}
+
+impl<T> TtlStore<T> {
+    pub fn new(ttl: Duration) -> Self {
+        Self::with_clock(ttl, SystemClock::new())
+    }
+}
+
+impl<T, C> TtlStore<T, C> {
+    /// as `new`, but reads time from provided `Clock`
+    pub fn with_clock(ttl: Duration, clock: C) -> Self {
+        Self::with_clock_and_hasher(ttl, clock, RandomState::new())
+    }
+}
+
+impl<T, C, S> TtlStore<T, C, S> {
+    /// as `with_clock`, but hashes the elements with provided `BuildHasher`
+    pub fn with_clock_and_hasher(ttl: Duration, clock: C, hash_builder: S) -> Self {
+        Self {
+            ttl,
+            clock,
+            deadlines: HashMap::with_hasher(hash_builder),
+            expiring: VecDeque::new(),
+        }
+    }
+
+    /// how long every element is remembered
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+}
+
+impl<T, C, S> TtlStore<T, C, S>
+where
+    T: Eq + Hash,
+    C: Clock,
+    S: BuildHasher,
+{
+    /// forgets the elements, whose time has run out by `now`
+    fn reclaim(&mut self, now: Duration) {
+        while let Some((deadline, _)) = self.expiring.front() {
+            if *deadline > now {
+                break;
+            }
+            let (deadline, item) = self.expiring.pop_front().expect("front exists");
+            if self.deadlines.get(&item) == Some(&deadline) {
+                self.deadlines.remove(&item);
+            }
+        }
+    }
+}
+
+impl<T, C, S> SeenStore<T> for TtlStore<T, C, S>
+where
+    T: Clone + Eq + Hash,
+    C: Clock,
+    S: BuildHasher,
+{
+    fn contains(&self, item: &T) -> bool {
+        self.deadlines
+            .get(item)
+            .is_some_and(|&deadline| deadline > self.clock.now())
+    }
+
+    fn observe(&mut self, item: &T) -> bool {
+        self.reclaim(self.clock.now());
+        self.deadlines.contains_key(item)
+    }
+
+    fn insert(&mut self, item: T) -> bool {
+        let now = self.clock.now();
+        self.reclaim(now);
+        if self.deadlines.contains_key(&item) {
+            return false;
+        }
+        let deadline = now + self.ttl;
+        self.deadlines.insert(item.clone(), deadline);
+        self.expiring.push_back((deadline, item));
+        true
+    }
+
+    fn len(&self) -> usize {
+        self.deadlines.len()
+    }
+
+    fn clear(&mut self) {
+        self.deadlines.clear();
+        self.expiring.clear();
+    }
+}
+
+/* # deduplicator */
+
+/// removes equal elements pushed one at a time, within `ttl` of the one which was let through
+pub type Deduplicator<T, C = SystemClock> = store::Deduplicator<T, TtlStore<T, C>>;
+
+impl<T, C> Deduplicator<T, C> {
+    /// remembers every pushed element for `ttl`, reading time from provided `Clock`
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use dedup::ttl::{Deduplicator, SystemClock};
+    ///
+    /// let mut dd = Deduplicator::with_ttl(Duration::from_secs(60), SystemClock::new());
+    /// assert!(dd.insert("alarm"));
+    /// assert!(!dd.insert("alarm"));
+    /// assert!(dd.insert("awaria"));
+    /// ```
+    pub fn with_ttl(ttl: Duration, clock: C) -> Self {
+        Self::with_store(TtlStore::with_clock(ttl, clock))
+    }
+}
+
+/* # dedup ttl */
+
+/// removes elements, which give an output from provided function equal to one let through less than `ttl` ago
+pub type DedupTtl<I, F, K, C = SystemClock> = DedupStoreByKey<I, TtlStore<K, C>, F, K>;
+
+/// provides the `dedup_ttl` method on `Iterator`s
+pub trait DedupTtlAdapter<F, K>: Iterator {
+    fn dedup_ttl(self, function: F, ttl: Duration) -> DedupTtl<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_store_by_key(TtlStore::new(ttl), function)
+    }
+
+    /// as `dedup_ttl`, but reads time from provided `Clock`
+    fn dedup_ttl_with_clock<C>(
+        self,
+        function: F,
+        ttl: Duration,
+        clock: C,
+    ) -> DedupTtl<Self, F, K, C>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+        C: Clock,
+    {
+        self.dedup_store_by_key(TtlStore::with_clock(ttl, clock), function)
+    }
+}
+
+impl<I, F, K> DedupTtlAdapter<F, K> for I where I: Iterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn deduplicate_empty_iterator() {
+        let og = Vec::<u8>::new();
+        let dp = og.iter().dedup_ttl(|&&n| n, MINUTE).count();
+        assert_eq!(dp, 0);
+    }
+
+    #[test]
+    fn repeat_within_ttl_is_suppressed() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og.chars().dedup_ttl(|&c| c, MINUTE).collect::<String>();
+        assert_eq!(&dp, "spor dwjnychlite");
+    }
+
+    #[test]
+    fn repeat_after_ttl_passes() {
+        let clock = ManualClock::new();
+        let og = [
+            ("alarm", 0),
+            ("alarm", 5),
+            ("awaria", 5),
+            ("alarm", 5),
+            ("awaria", 1),
+        ];
+        let dp = og
+            .into_iter()
+            .inspect(|&(_, minutes)| clock.advance(MINUTE * minutes))
+            .dedup_ttl_with_clock(|&(event, _)| event, MINUTE * 10, &clock)
+            .map(|(event, _)| event)
+            .collect::<Vec<_>>();
+        assert_eq!(dp, ["alarm", "awaria", "alarm"]);
+    }
+
+    #[test]
+    fn expired_elements_are_reclaimed() {
+        let clock = ManualClock::new();
+        let mut dd = Deduplicator::with_ttl(MINUTE, &clock);
+        assert!(dd.insert("raz"));
+        assert!(dd.insert("dwa"));
+        assert!(!dd.insert("raz"));
+        clock.advance(MINUTE);
+        assert!(dd.would_emit(&"raz"));
+        assert_eq!(dd.len(), 2);
+        assert!(dd.insert("trzy"));
+        assert_eq!(dd.len(), 1);
+        assert!(dd.insert("raz"));
+        assert!(!dd.would_emit(&"trzy"));
+    }
+}
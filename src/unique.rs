@@ -0,0 +1,247 @@
+use alloc::{
+    collections::BTreeMap,
+    vec::{self, Vec},
+};
+#[cfg(feature = "std")]
+use std::collections::{hash_map::RandomState, HashMap};
+
+use crate::duplicates::{Occurrence, OccurrenceStore};
+
+/* # unique only by key */
+
+/// yields elements, which give an output from provided function different from that of every other element,
+/// in their original order
+///
+/// whether an element is unique is known only at the end, so the first occurrences are buffered until then;
+/// see `dedup` for the streaming adapters, which drop repeated runs only
+#[derive(Debug, Clone)]
+pub struct UniqueOnlyStoreByKey<I, S, F, K>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+{
+    iterator: I,
+    seen: S,
+    function: F,
+    buffered: Option<vec::IntoIter<(usize, Option<I::Item>)>>,
+}
+
+impl<I, S, F, K> UniqueOnlyStoreByKey<I, S, F, K>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+{
+    /// the occurrences of the outputs seen so far, which are all of them once iteration has started
+    pub fn seen(&self) -> &S {
+        &self.seen
+    }
+}
+
+impl<I, S, F, K> Iterator for UniqueOnlyStoreByKey<I, S, F, K>
+where
+    I: Iterator,
+    S: OccurrenceStore<K>,
+    F: Fn(&I::Item) -> K,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let buffered = match &mut self.buffered {
+            Some(buffered) => buffered,
+            None => {
+                let mut firsts = Vec::new();
+                for (index, item) in self.iterator.by_ref().enumerate() {
+                    let occurrence = self.seen.record((self.function)(&item), index);
+                    match occurrence.count {
+                        1 => firsts.push((index, Some(item))),
+                        2 => {
+                            let first = firsts
+                                .binary_search_by_key(&occurrence.first, |&(index, _)| index)
+                                .expect("first occurrence is buffered");
+                            firsts[first].1 = None;
+                        }
+                        _ => {}
+                    }
+                }
+                self.buffered.insert(firsts.into_iter())
+            }
+        };
+        buffered.find_map(|(_, item)| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.buffered {
+            Some(buffered) => (0, Some(buffered.len())),
+            None => (0, self.iterator.size_hint().1),
+        }
+    }
+}
+
+/// provides the `unique_only_store_by_key` method on `Iterator`s
+pub trait UniqueOnlyStoreByKeyAdapter<S, F, K>: Iterator {
+    fn unique_only_store_by_key(self, seen: S, function: F) -> UniqueOnlyStoreByKey<Self, S, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        UniqueOnlyStoreByKey {
+            iterator: self,
+            seen,
+            function,
+            buffered: None,
+        }
+    }
+}
+
+impl<I, S, F, K> UniqueOnlyStoreByKeyAdapter<S, F, K> for I where I: Iterator {}
+
+/* # unique only */
+
+/// yields elements not equal to any other element
+pub type UniqueOnlyStore<I, S> = UniqueOnlyStoreByKey<
+    I,
+    S,
+    fn(&<I as Iterator>::Item) -> <I as Iterator>::Item,
+    <I as Iterator>::Item,
+>;
+
+/// provides the `unique_only_store` method on `Iterator`s
+pub trait UniqueOnlyStoreAdapter<S>: Iterator {
+    fn unique_only_store(self, seen: S) -> UniqueOnlyStore<Self, S>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        self.unique_only_store_by_key(seen, Self::Item::clone)
+    }
+}
+
+impl<I, S> UniqueOnlyStoreAdapter<S> for I where I: Iterator {}
+
+/* # unique only hash */
+
+/// yields elements not equal to any other element, counting occurrences in a `HashMap`
+#[cfg(feature = "std")]
+pub type UniqueOnlyHash<I, S = RandomState> =
+    UniqueOnlyStore<I, HashMap<<I as Iterator>::Item, Occurrence, S>>;
+
+/// provides the `unique_only_hash` method on `Iterator`s
+#[cfg(feature = "std")]
+pub trait UniqueOnlyHashAdapter: Iterator {
+    fn unique_only_hash(self) -> UniqueOnlyHash<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        self.unique_only_store(HashMap::new())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I> UniqueOnlyHashAdapter for I where I: Iterator {}
+
+/* # unique only hash by key */
+
+/// yields elements, whose output from provided function is unique, counting occurrences in a `HashMap`
+#[cfg(feature = "std")]
+pub type UniqueOnlyHashByKey<I, F, K, S = RandomState> =
+    UniqueOnlyStoreByKey<I, HashMap<K, Occurrence, S>, F, K>;
+
+/// provides the `unique_only_hash_by_key` method on `Iterator`s
+#[cfg(feature = "std")]
+pub trait UniqueOnlyHashByKeyAdapter<F, K>: Iterator {
+    fn unique_only_hash_by_key(self, function: F) -> UniqueOnlyHashByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.unique_only_store_by_key(HashMap::new(), function)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I, F, K> UniqueOnlyHashByKeyAdapter<F, K> for I where I: Iterator {}
+
+/* # unique only ord */
+
+/// yields elements not equal to any other element, counting occurrences in a `BTreeMap`
+pub type UniqueOnlyOrd<I> = UniqueOnlyStore<I, BTreeMap<<I as Iterator>::Item, Occurrence>>;
+
+/// provides the `unique_only_ord` method on `Iterator`s
+pub trait UniqueOnlyOrdAdapter: Iterator {
+    fn unique_only_ord(self) -> UniqueOnlyOrd<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        self.unique_only_store(BTreeMap::new())
+    }
+}
+
+impl<I> UniqueOnlyOrdAdapter for I where I: Iterator {}
+
+/* # unique only ord by key */
+
+/// yields elements, whose output from provided function is unique, counting occurrences in a `BTreeMap`
+pub type UniqueOnlyOrdByKey<I, F, K> = UniqueOnlyStoreByKey<I, BTreeMap<K, Occurrence>, F, K>;
+
+/// provides the `unique_only_ord_by_key` method on `Iterator`s
+pub trait UniqueOnlyOrdByKeyAdapter<F, K>: Iterator {
+    fn unique_only_ord_by_key(self, function: F) -> UniqueOnlyOrdByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.unique_only_store_by_key(BTreeMap::new(), function)
+    }
+}
+
+impl<I, F, K> UniqueOnlyOrdByKeyAdapter<F, K> for I where I: Iterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_iterator_has_no_unique_elements() {
+        let og = Vec::<u8>::new();
+        assert_eq!(og.iter().unique_only_ord().count(), 0);
+        assert_eq!(og.iter().unique_only_store(Vec::new()).count(), 0);
+    }
+
+    #[test]
+    fn unique_only_keeps_singletons_in_order() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og.chars().unique_only_ord().collect::<String>();
+        assert_eq!(&dp, "dwchlie");
+        let og: [i32; 7] = [10, 20, 20, 21, 30, 30, 20];
+        let mut dp = og.into_iter().unique_only_ord();
+        assert_eq!(dp.next(), Some(10));
+        assert_eq!(dp.seen()[&20].count, 3);
+        assert_eq!(dp.collect::<Vec<_>>(), [21]);
+    }
+
+    #[test]
+    fn unique_only_by_key() {
+        let og = ["Raz", "dwa", "RAZ", "Trzy", "cztery", "trzy", "raz"];
+        let dp = og
+            .into_iter()
+            .unique_only_ord_by_key(|word| word.len())
+            .collect::<Vec<_>>();
+        assert_eq!(dp, ["cztery"]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn unique_only_hash_matches_ord() {
+        let og = "ssporrro podwojjonnyych litterr";
+        let dp = og.chars().unique_only_hash().collect::<String>();
+        assert_eq!(&dp, "dwchlie");
+        let og = ["Raz", "dwa", "RAZ", "Trzy", "cztery", "trzy", "raz"];
+        let dp = og
+            .into_iter()
+            .unique_only_hash_by_key(|word| word.to_lowercase())
+            .collect::<Vec<_>>();
+        assert_eq!(dp, ["dwa", "cztery"]);
+    }
+}
@@ -0,0 +1,192 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap, VecDeque},
+    hash::{BuildHasher, Hash},
+};
+
+/* # dedup window by key */
+
+/// removes elements, which give an output from provided function equal to that of one of the previous `n` elements
+///
+/// only the outputs of the last `n` elements are remembered, in a ring buffer together with a count of each one
+#[derive(Debug, Clone)]
+pub struct DedupWindowByKey<I, F, K, S = RandomState>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+{
+    iterator: I,
+    function: F,
+    size: usize,
+    window: VecDeque<K>,
+    counts: HashMap<K, usize, S>,
+}
+
+impl<I, F, K, S> DedupWindowByKey<I, F, K, S>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+{
+    /// the outputs of the last elements, from the oldest one
+    pub fn window(&self) -> impl Iterator<Item = &K> {
+        self.window.iter()
+    }
+}
+
+impl<I, F, K, S> Iterator for DedupWindowByKey<I, F, K, S>
+where
+    I: Iterator,
+    F: Fn(&I::Item) -> K,
+    K: Clone + Eq + Hash,
+    S: BuildHasher,
+{
+    type Item = I::Item;
+
+    #[allow(clippy::while_let_on_iterator, reason = "seems more readable here")]
+    fn next(&mut self) -> Option<I::Item> {
+        while let Some(item) = self.iterator.next() {
+            if self.size == 0 {
+                return Some(item);
+            }
+            let key = (self.function)(&item);
+            let count = self.counts.entry(key.clone()).or_insert(0);
+            *count += 1;
+            let new = *count == 1;
+            self.window.push_back(key);
+            if self.window.len() > self.size {
+                let oldest = self.window.pop_front().expect("window is not empty");
+                if let Some(count) = self.counts.get_mut(&oldest) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.counts.remove(&oldest);
+                    }
+                }
+            }
+            if new {
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iterator.size_hint();
+        if self.size == 0 {
+            return (lower, upper);
+        }
+        (usize::from(lower > 0 && self.window.is_empty()), upper)
+    }
+}
+
+/// provides the `dedup_window_by_key` method on `Iterator`s
+pub trait DedupWindowByKeyAdapter<F, K>: Iterator {
+    fn dedup_window_by_key(self, size: usize, function: F) -> DedupWindowByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        self.dedup_window_by_key_with_hasher(size, function, RandomState::new())
+    }
+
+    /// as `dedup_window_by_key`, but hashes the outputs with provided `BuildHasher`
+    fn dedup_window_by_key_with_hasher<S>(
+        self,
+        size: usize,
+        function: F,
+        hash_builder: S,
+    ) -> DedupWindowByKey<Self, F, K, S>
+    where
+        Self: Sized,
+        F: Fn(&Self::Item) -> K,
+    {
+        DedupWindowByKey {
+            iterator: self,
+            function,
+            size,
+            window: VecDeque::new(),
+            counts: HashMap::with_hasher(hash_builder),
+        }
+    }
+}
+
+impl<I, F, K> DedupWindowByKeyAdapter<F, K> for I where I: Iterator {}
+
+/* # dedup window */
+
+/// removes elements equal to one of the previous `n` elements
+pub type DedupWindow<I, S = RandomState> = DedupWindowByKey<
+    I,
+    fn(&<I as Iterator>::Item) -> <I as Iterator>::Item,
+    <I as Iterator>::Item,
+    S,
+>;
+
+/// provides the `dedup_window` method on `Iterator`s
+pub trait DedupWindowAdapter: Iterator {
+    fn dedup_window(self, size: usize) -> DedupWindow<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        self.dedup_window_with_hasher(size, RandomState::new())
+    }
+
+    /// as `dedup_window`, but hashes the elements with provided `BuildHasher`
+    fn dedup_window_with_hasher<S>(self, size: usize, hash_builder: S) -> DedupWindow<Self, S>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        self.dedup_window_by_key_with_hasher(size, Self::Item::clone, hash_builder)
+    }
+}
+
+impl<I> DedupWindowAdapter for I where I: Iterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deduplicate_empty_iterator() {
+        let og = Vec::<u8>::new();
+        let dp = og.iter().dedup_window(3).count();
+        assert_eq!(dp, 0);
+    }
+
+    #[test]
+    fn repeat_after_window_passes() {
+        let og: [i32; 9] = [10, 20, 10, 30, 40, 10, 20, 20, 20];
+        let dp = og.into_iter().dedup_window(2).collect::<Vec<_>>();
+        assert_eq!(dp, [10, 20, 30, 40, 10, 20]);
+        let dp = og.into_iter().dedup_window(3).collect::<Vec<_>>();
+        assert_eq!(dp, [10, 20, 30, 40, 20]);
+    }
+
+    #[test]
+    fn duplicates_count_towards_window() {
+        let og = "abbba";
+        let dp = og.chars().dedup_window(3).collect::<String>();
+        assert_eq!(&dp, "aba");
+    }
+
+    #[test]
+    fn empty_window_keeps_everything() {
+        let og = "ssporrro";
+        let dp = og.chars().dedup_window(0).collect::<String>();
+        assert_eq!(&dp, og);
+    }
+
+    #[test]
+    fn window_by_key_is_bounded() {
+        let og = ["Raz", "dwa", "RAZ", "Trzy", "DWA", "trzy", "raz"];
+        let mut dp = og
+            .into_iter()
+            .dedup_window_by_key(2, |word| word.to_lowercase());
+        assert_eq!(
+            dp.by_ref().collect::<Vec<_>>(),
+            ["Raz", "dwa", "Trzy", "DWA", "raz"]
+        );
+        assert_eq!(dp.window().collect::<Vec<_>>(), ["trzy", "raz"]);
+        assert!(dp.counts.len() <= 2);
+    }
+}